@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+/// Seconds between a bot's decisions, it keeps acting on its last decision in between
+pub const BOT_THINK_INTERVAL: f32 = 0.25;
+/// How far a bot can see other players
+pub const BOT_SIGHT_RANGE: f32 = 40.0;
+/// Fraction of its health below which a bot of middling aggression runs from a fight
+pub const BOT_RETREAT_HEALTH: f32 = 0.3;
+/// Seconds between a bot's swings, throws and item uses
+pub const BOT_ATTACK_INTERVAL: f32 = 0.6;
+
+/// How hard a bot is to beat
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BotDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl BotDifficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BotDifficulty::Easy => "Easy",
+            BotDifficulty::Normal => "Normal",
+            BotDifficulty::Hard => "Hard",
+        }
+    }
+
+    pub fn profile(&self) -> BotProfile {
+        match self {
+            BotDifficulty::Easy => BotProfile {
+                reaction_time: 0.8,
+                aim_error: 0.15,
+                turn_speed: 600.0,
+                item_awareness: 15.0,
+                aggression: 0.3,
+            },
+            BotDifficulty::Normal => BotProfile {
+                reaction_time: 0.4,
+                aim_error: 0.06,
+                turn_speed: 1200.0,
+                item_awareness: 30.0,
+                aggression: 0.5,
+            },
+            BotDifficulty::Hard => BotProfile {
+                reaction_time: 0.15,
+                aim_error: 0.02,
+                turn_speed: 2400.0,
+                item_awareness: 45.0,
+                aggression: 0.8,
+            },
+        }
+    }
+}
+
+/// How a bot of a [`BotDifficulty`] plays
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BotProfile {
+    /// Seconds between spotting an enemy and reacting to it
+    pub reaction_time: f32,
+    /// Largest angle in radians the aim of the bot is off by
+    pub aim_error: f32,
+    /// How fast the bot turns, in pixels per second a player would move the mouse,
+    /// so its [`CameraSensitivity`](crate::components::camera::CameraSensitivity) scales it like a player's
+    pub turn_speed: f32,
+    /// How far the bot goes out of its way for an item
+    pub item_awareness: f32,
+    /// From 0 to 1, how eagerly the bot fights and how long it keeps fighting while hurt
+    pub aggression: f32,
+}
+
+impl BotProfile {
+    /// Fraction of its health below which the bot runs from a fight, bots at full aggression never run
+    pub fn retreat_health(&self) -> f32 {
+        BOT_RETREAT_HEALTH * 2.0 * (1.0 - self.aggression)
+    }
+}
+
+/// What a bot is busy with, chosen by scoring each option and taking the best one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BotGoal {
+    /// Walk towards a point in the arena
+    Roam(Vec3),
+    /// Walk onto an item to pick it up
+    Collect(Entity),
+    /// Fight a player it can see
+    Engage(Entity),
+    /// Get away from a player while hurt, healing once far enough away
+    Retreat(Entity),
+}
+
+/**
+A player controlled by the AI instead of a human.
+It fills the same [`PlayerIntent`](crate::components::player::PlayerIntent) the keyboard and mouse fill for the local player.
+*/
+#[derive(Debug, Component)]
+pub struct Bot {
+    pub difficulty: BotDifficulty,
+    pub goal: BotGoal,
+    /// Points the bot walks through to reach its goal, found on the navigation grid
+    pub path: Vec<Vec3>,
+    pub think_timer: Timer,
+    /// Keeps the bot from attacking every frame
+    pub attack_timer: Timer,
+    /// Enemies in sight, with the time each was first spotted at
+    pub spotted: Vec<(Entity, f32)>,
+    /// Goal against an enemy the bot hasn't reacted to yet, with its path, taken up once the reaction time has passed
+    pub pending: Option<(BotGoal, Vec<Vec3>)>,
+    /// How far off the bot aims, picked again with every decision
+    pub aim_offset: Quat,
+}
+
+impl Bot {
+    pub fn new(difficulty: BotDifficulty) -> Self {
+        Bot {
+            difficulty,
+            goal: BotGoal::Roam(Vec3::ZERO),
+            path: Vec::new(),
+            think_timer: Timer::from_seconds(BOT_THINK_INTERVAL, TimerMode::Repeating),
+            attack_timer: Timer::from_seconds(BOT_ATTACK_INTERVAL, TimerMode::Once),
+            spotted: Vec::new(),
+            pending: None,
+            aim_offset: Quat::IDENTITY,
+        }
+    }
+
+    /// Whether the bot has had the time to react to the enemy a goal is about, goals without an enemy need no reaction
+    pub fn has_reacted(&self, goal: &BotGoal, now: f32) -> bool {
+        match goal {
+            BotGoal::Engage(enemy) | BotGoal::Retreat(enemy) => self
+                .spotted
+                .iter()
+                .any(|(spotted, since)| spotted == enemy && now - since >= self.difficulty.profile().reaction_time),
+            BotGoal::Roam(_) | BotGoal::Collect(_) => true,
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use bevy::prelude::*;
+
+use crate::components::world::EntityName;
+
+/**
+The player behind some damage.
+The name is kept so they can still be credited after their entity is gone.
+*/
+#[derive(Debug, Clone)]
+pub struct Instigator {
+    pub entity: Entity,
+    pub name: String,
+}
+
+impl Instigator {
+    pub fn new(entity: Entity, name: &EntityName) -> Self {
+        Self {
+            entity,
+            name: name.name().to_string(),
+        }
+    }
+}
+
+/// Kind of damage, deciding what can protect against it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DamageType {
+    /// Cuts, blunt hits and thrown objects
+    #[default]
+    Physical,
+    Explosive,
+    /// Goes straight through armor
+    Piercing,
+}
+
+impl DamageType {
+    pub fn bypasses_armor(&self) -> bool {
+        matches!(self, DamageType::Piercing)
+    }
+}
+
+/**
+Who dealt damage and with what.
+Every entity that deals damage carries one, and it is passed along with the damage.
+*/
+#[derive(Debug, Clone, Component)]
+pub struct DamageSource {
+    /// `None` for damage that comes from the world itself
+    pub instigator: Option<Instigator>,
+    /// Name of the weapon, item or hazard that dealt the damage
+    pub cause: String,
+}
+
+impl DamageSource {
+    pub fn new(instigator: Instigator, cause: &str) -> Self {
+        Self {
+            instigator: Some(instigator),
+            cause: String::from(cause),
+        }
+    }
+
+    /// Whether the damage was caused by the given entity
+    pub fn is_from(&self, entity: Entity) -> bool {
+        self.instigator
+            .as_ref()
+            .is_some_and(|instigator| instigator.entity == entity)
+    }
+
+    pub fn instigator_name(&self) -> Option<&str> {
+        self.instigator.as_ref().map(|instigator| instigator.name.as_str())
+    }
+}
+
+/**
+A short lived hit area in front of a player swinging a melee weapon.
+Each player inside it is damaged at most once.
+*/
+#[derive(Component)]
+pub struct MeleeSwing {
+    pub timer: Timer,
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub radius: f32,
+    /// Where the swinging player stood
+    pub origin: Vec3,
+    /// Impulse given to a hit player
+    pub knockback: Vec3,
+    pub hit_stun: f32,
+    pub hits: Vec<Entity>,
+}
+
+/// What happens when a fused throwable goes off
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Detonation {
+    /// Damages and pushes everything around it, less the further away it is
+    Explosion { radius: f32, damage: f32, knockback: f32 },
+    /// Leaves a volume in the world that applies a status to players inside
+    Field { kind: FieldKind, radius: f32, duration: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    /// Blocks vision, players inside are [`Obscured`]
+    Smoke,
+    /// Players inside are [`Slowed`] to the given fraction of their speed
+    Slow { factor: f32 },
+}
+
+/// How long a throwable flies or rolls before it goes off, and what it does then
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuseSpec {
+    pub seconds: f32,
+    pub detonation: Detonation,
+}
+
+/// A thrown object that goes off once its timer finishes
+#[derive(Component)]
+pub struct Fuse {
+    pub timer: Timer,
+    pub detonation: Detonation,
+}
+
+impl Fuse {
+    pub fn new(spec: FuseSpec) -> Self {
+        Self {
+            timer: Timer::from_seconds(spec.seconds, TimerMode::Once),
+            detonation: spec.detonation,
+        }
+    }
+}
+
+/// Describes how an object is thrown or fired into the world
+#[derive(Clone)]
+pub struct ThrowParams {
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+    pub speed: f32,
+    /// Angular velocity the object is thrown with
+    pub spin: Vec3,
+    pub radius: f32,
+    pub gravity_scale: f32,
+    /// Damage dealt to the first player it hits
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub knockback: f32,
+    pub hit_stun: f32,
+    /// How much speed is kept when bouncing off the world
+    pub restitution: f32,
+}
+
+impl ThrowParams {
+    /// Parameters of a regular throw with the given visuals and speed
+    pub fn new(mesh: Handle<Mesh>, material: Handle<StandardMaterial>, speed: f32) -> Self {
+        Self {
+            mesh,
+            material,
+            speed,
+            spin: Vec3::ZERO,
+            radius: 0.5,
+            gravity_scale: 1.0,
+            damage: 0.0,
+            damage_type: DamageType::Physical,
+            knockback: 0.0,
+            hit_stun: 0.0,
+            restitution: 0.3,
+        }
+    }
+
+    pub fn with_spin(mut self, spin: Vec3) -> Self {
+        self.spin = spin;
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_gravity_scale(mut self, gravity_scale: f32) -> Self {
+        self.gravity_scale = gravity_scale;
+        self
+    }
+
+    pub fn with_damage(mut self, damage: f32) -> Self {
+        self.damage = damage;
+        self
+    }
+
+    pub fn with_damage_type(mut self, damage_type: DamageType) -> Self {
+        self.damage_type = damage_type;
+        self
+    }
+
+    pub fn with_knockback(mut self, knockback: f32) -> Self {
+        self.knockback = knockback;
+        self
+    }
+
+    pub fn with_hit_stun(mut self, hit_stun: f32) -> Self {
+        self.hit_stun = hit_stun;
+        self
+    }
+
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution;
+        self
+    }
+}
+
+/// A timed volume in the world that applies a status to players inside
+#[derive(Component)]
+pub struct AreaEffect {
+    pub kind: FieldKind,
+    pub radius: f32,
+    pub timer: Timer,
+}
+
+/// Present on a player who was knocked back.
+/// Movement keeps their momentum instead of overriding it until the timer finishes.
+#[derive(Component)]
+pub struct Staggered {
+    pub timer: Timer,
+}
+
+/// Present on a player who was hit hard enough that they can't attack or use items
+#[derive(Component)]
+pub struct Stunned {
+    pub timer: Timer,
+}
+
+/**
+Present on a player holding up their melee weapon to block.
+Hits from the front are reduced at the cost of durability,
+and hits right after raising the block are parried.
+*/
+#[derive(Component)]
+pub struct Blocking {
+    /// Seconds since the block was raised
+    pub elapsed: f32,
+    /// Whether the block can parry, a block raised again too soon after the last one can't
+    pub can_parry: bool,
+}
+
+impl Blocking {
+    /// How long after raising the block a hit is parried
+    pub const PARRY_WINDOW: f32 = 0.2;
+    /// Seconds after lowering a block before the next one can parry again
+    pub const PARRY_COOLDOWN: f32 = 0.6;
+
+    pub fn new(can_parry: bool) -> Self {
+        Self { elapsed: 0.0, can_parry }
+    }
+
+    pub fn is_parrying(&self) -> bool {
+        self.can_parry && self.elapsed <= Self::PARRY_WINDOW
+    }
+}
+
+/// Present on a player who lowered their block recently, so tapping block doesn't parry everything
+#[derive(Component)]
+pub struct ParryCooldown {
+    pub timer: Timer,
+}
+
+/// Present on a player who took damage recently, their armor doesn't regenerate until it runs out
+#[derive(Component)]
+pub struct InCombat {
+    pub timer: Timer,
+}
+
+/// Status of a player that moves slower, refreshed while they stay in a slow field
+#[derive(Component)]
+pub struct Slowed {
+    /// Fraction of the normal speed the player keeps
+    pub factor: f32,
+    pub timer: Timer,
+}
+
+/// Status of a player hidden inside smoke, refreshed while they stay in it
+#[derive(Component)]
+pub struct Obscured {
+    pub timer: Timer,
+}
+
+/// Present on a dead player waiting to spawn again
+#[derive(Component)]
+pub struct Respawning {
+    pub timer: Timer,
+}
+
+/// Present on a player who is out of the match for good, in modes without respawns
+#[derive(Component)]
+pub struct Eliminated;
+
+/**
+Present on a player who just spawned, they can't be damaged until the timer finishes.
+Attacking ends the protection early.
+*/
+#[derive(Component)]
+pub struct SpawnProtection {
+    pub timer: Timer,
+}
+
+/// Marker for the bubble shown around a player with spawn protection
+#[derive(Component)]
+pub struct SpawnProtectionBubble;
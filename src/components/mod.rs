@@ -4,6 +4,12 @@ use bevy::prelude::{Component};
 pub mod player;
 pub mod camera;
 pub mod ui;
+pub mod combat;
+pub mod score;
+pub mod team;
+pub mod objective;
+pub mod bot;
+pub mod prediction;
 
 pub mod world;
 // #[derive(Component)]
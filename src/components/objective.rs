@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+/// Radius of the hill's area
+pub const HILL_RADIUS: f32 = 3.0;
+/// Height of the hill's area above the ground it stands on
+pub const HILL_HEIGHT: f32 = 3.0;
+/// Seconds a player alone on the hill needs to capture it
+pub const CAPTURE_TIME: f32 = 3.0;
+/// Seconds the hill stays at one position before it moves on
+pub const HILL_ROTATION_TIME: f32 = 60.0;
+
+/**
+The hill of king of the hill matches.
+A player alone on it captures it, then scores for every second they hold it alone.
+It freezes while more than one player is on it, and moves between its positions during the match.
+*/
+#[derive(Debug, Component)]
+pub struct CaptureZone {
+    /// Whether the current match is played for the hill
+    pub active: bool,
+    /// Player the hill belongs to, they score while they hold it alone
+    pub owner: Option<Entity>,
+    /// Player taking the hill from its owner, with their progress from 0 to 1
+    pub capturer: Option<Entity>,
+    pub progress: f32,
+    /// Whether more than one player is on the hill
+    pub contested: bool,
+    /// Ground points the hill moves between, in order
+    pub positions: Vec<Vec3>,
+    pub current: usize,
+    pub rotation_timer: Timer,
+    /// Time towards the owner's next second of holding
+    pub hold_timer: Timer,
+}
+
+impl CaptureZone {
+    pub fn new(positions: Vec<Vec3>) -> Self {
+        Self {
+            active: false,
+            owner: None,
+            capturer: None,
+            progress: 0.0,
+            contested: false,
+            positions,
+            current: 0,
+            rotation_timer: Timer::from_seconds(HILL_ROTATION_TIME, TimerMode::Repeating),
+            hold_timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+        }
+    }
+
+    /// Center of the hill's area at its current position
+    pub fn center(&self) -> Vec3 {
+        self.positions.get(self.current).copied().unwrap_or_default() + Vec3::Y * HILL_HEIGHT / 2.0
+    }
+
+    /// Moves the hill to the position at `index` and leaves it unowned
+    pub fn move_to(&mut self, index: usize) {
+        self.current = index % self.positions.len().max(1);
+        self.owner = None;
+        self.capturer = None;
+        self.progress = 0.0;
+        self.contested = false;
+        self.hold_timer.reset();
+    }
+}
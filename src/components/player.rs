@@ -1,11 +1,42 @@
 /* player.rs */
+use std::collections::HashMap;
 use bevy::prelude::*;
-use bevy_rapier3d::prelude::*;
-use crate::components::world::ThrewObject;
+use crate::components::combat::{DamageSource, DamageType, FuseSpec, Instigator, ThrowParams};
+use crate::events::notification::{NotificationEvent, NotificationPriority};
 
 #[derive(Component)]
 pub struct Player;
 
+/// Marker for the player controlled on this machine, the HUD shows their view of the game
+#[derive(Component)]
+pub struct LocalPlayer;
+
+/**
+What a player wants to do this frame, filled from the keyboard and mouse for the local player
+and by the AI for bots. The gameplay systems only read intents, so both are handled the same way.
+Fields for actions that happen once, like `attack` or `jump`, are only set on the frame they start.
+*/
+#[derive(Debug, Clone, Default, Component)]
+pub struct PlayerIntent {
+    /// Direction to walk in on the ground, in world space
+    pub movement: Vec3,
+    /// Direction to aim in, `None` keeps the current aim
+    pub look: Option<Vec3>,
+    pub jump: bool,
+    /// Swing the held melee weapon
+    pub attack: bool,
+    /// Keep firing the held ranged weapon
+    pub fire: bool,
+    pub block: bool,
+    /// Throw the held weapon
+    pub throw: bool,
+    pub reload: bool,
+    pub use_item: bool,
+    pub drop_item: bool,
+    pub switch_weapon: Option<WeaponSlotKind>,
+    /// Moves the selected inventory slot forwards or backwards
+    pub cycle_item: i32,
+}
 
 #[derive(Debug, Component)]
 pub struct JumpAbility {
@@ -23,7 +54,19 @@ impl Default for JumpAbility {
 #[derive(Component)]
 pub struct PlayerStats {
     pub health: f32,
+    pub max_health: f32,
     pub(crate) speed: f32,
+    /// Fraction of incoming damage that is ignored, from 0.0 to 1.0
+    pub damage_resistance: f32,
+    /// Extra distance at which items are picked up without touching them
+    pub pickup_radius: f32,
+    /// Shield layer that takes damage before health
+    pub armor: f32,
+    pub max_armor: f32,
+    /// Fraction of each hit the armor takes, 1.0 means health is untouched while armor lasts
+    pub armor_absorption: f32,
+    /// Armor regained per second once the player has been out of combat for a while
+    pub armor_regen: f32,
 }
 
 #[derive(Component)]
@@ -41,10 +84,78 @@ impl Default for Direction {
 
 impl Default for PlayerStats {
     fn default() -> Self {
-        Self { health: 100.0, speed: 15.0 }
+        Self {
+            health: 100.0,
+            max_health: 100.0,
+            speed: 15.0,
+            damage_resistance: 0.0,
+            pickup_radius: 0.0,
+            armor: 0.0,
+            max_armor: 100.0,
+            armor_absorption: 0.5,
+            armor_regen: 0.0,
+        }
+    }
+}
+
+impl PlayerStats {
+    /// Applies the difference between two modifier totals to the stats
+    pub fn apply_modifier_delta(&mut self, old: &StatModifier, new: &StatModifier) {
+        self.speed += new.move_speed - old.move_speed;
+        self.damage_resistance += new.damage_resistance - old.damage_resistance;
+        self.pickup_radius += new.pickup_radius - old.pickup_radius;
+        self.armor_regen += new.armor_regen - old.armor_regen;
+
+        let max_health_delta = new.max_health - old.max_health;
+        self.max_health += max_health_delta;
+        // Gaining max health also grants the extra health, losing it only clamps
+        self.health = (self.health + max_health_delta.max(0.0)).min(self.max_health);
+    }
+
+    /// Restores armor up to the maximum, weaker armor tops up better armor without downgrading it
+    pub fn add_armor(&mut self, amount: f32, absorption: f32) {
+        if self.armor <= 0.0 {
+            self.armor_absorption = absorption;
+        } else {
+            self.armor_absorption = self.armor_absorption.max(absorption);
+        }
+        self.armor = (self.armor + amount).min(self.max_armor);
+    }
+
+    /// Lets the armor soak up its share of a hit and returns the damage left for health
+    pub fn absorb_with_armor(&mut self, damage: f32) -> f32 {
+        let absorbed = (damage * self.armor_absorption).min(self.armor);
+        self.armor -= absorbed;
+        damage - absorbed
+    }
+}
+
+/// Stat changes granted by a passive item while it is held
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatModifier {
+    pub move_speed: f32,
+    pub max_health: f32,
+    pub damage_resistance: f32,
+    pub pickup_radius: f32,
+    pub armor_regen: f32,
+}
+
+impl StatModifier {
+    pub fn combine(&self, other: &StatModifier) -> StatModifier {
+        StatModifier {
+            move_speed: self.move_speed + other.move_speed,
+            max_health: self.max_health + other.max_health,
+            damage_resistance: self.damage_resistance + other.damage_resistance,
+            pickup_radius: self.pickup_radius + other.pickup_radius,
+            armor_regen: self.armor_regen + other.armor_regen,
+        }
     }
 }
 
+/// The passive modifiers currently applied to a player's [`PlayerStats`]
+#[derive(Component, Default)]
+pub struct AppliedModifiers(pub StatModifier);
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ItemType {
     Passive,
@@ -57,8 +168,58 @@ pub enum ItemType {
 pub enum ItemEffect {
     IncreaseSpeed { amount: f32, duration: f32 }, // Increase movement speed
     Heal(f32),         // Restore health
-    Throw(Handle<Mesh>, Handle<StandardMaterial>),       // Throw in a direction
+    Throw { mesh: Handle<Mesh>, material: Handle<StandardMaterial>, damage: f32, speed: f32, fuse: Option<FuseSpec> }, // Throw in a direction
     WeaponItem(Weapon),  // Melee attack with durability
+    Passive(StatModifier), // Applied while the item is in the inventory
+    Ammo { ammo_type: AmmoType, amount: u16 }, // Refill a ranged weapon
+    Armor { amount: f32, absorption: f32 }, // Restore armor, with how much of each hit it takes
+}
+
+/// Broad grouping of item effects, used to share cooldowns between similar items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ItemCategory {
+    Buff,
+    Heal,
+    Throw,
+    Weapon,
+    Passive,
+    Ammo,
+    Armor,
+}
+
+impl ItemEffect {
+    pub fn category(&self) -> ItemCategory {
+        match self {
+            ItemEffect::IncreaseSpeed { .. } => ItemCategory::Buff,
+            ItemEffect::Heal(_) => ItemCategory::Heal,
+            ItemEffect::Throw { .. } => ItemCategory::Throw,
+            ItemEffect::WeaponItem(_) => ItemCategory::Weapon,
+            ItemEffect::Passive(_) => ItemCategory::Passive,
+            ItemEffect::Ammo { .. } => ItemCategory::Ammo,
+            ItemEffect::Armor { .. } => ItemCategory::Armor,
+        }
+    }
+}
+
+/// Decides which items share a cooldown once one of them is used
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CooldownScope {
+    /// Only items with the same name share the cooldown
+    Item,
+    /// Every item in the same [`ItemCategory`] shares the cooldown
+    Category,
+}
+
+#[derive(Debug, Clone)]
+pub struct ItemCooldown {
+    pub duration: f32,
+    pub scope: CooldownScope,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CooldownKey {
+    Item(String),
+    Category(ItemCategory),
 }
 
 #[derive(Component)]
@@ -73,6 +234,66 @@ pub struct Item {
     pub description: String,
     pub type_:ItemType,
     pub effect: ItemEffect,
+    /// Seconds the player has to channel the item before the effect applies.
+    /// Zero means the item is used instantly.
+    pub use_time: f32,
+    pub cooldown: Option<ItemCooldown>,
+}
+
+impl Item {
+    /// The key this item's cooldown is stored under, if it has one
+    pub fn cooldown_key(&self) -> Option<CooldownKey> {
+        self.cooldown.as_ref().map(|cooldown| match cooldown.scope {
+            CooldownScope::Item => CooldownKey::Item(self.name.clone()),
+            CooldownScope::Category => CooldownKey::Category(self.effect.category()),
+        })
+    }
+}
+
+/// Present on a dropped item until it can be picked up again
+#[derive(Component)]
+pub struct PickupDelay(pub Timer);
+
+/// Cooldowns that are still running after an item was used
+#[derive(Component, Default)]
+pub struct ItemCooldowns {
+    timers: HashMap<CooldownKey, Timer>,
+}
+
+impl ItemCooldowns {
+    /// Returns true if the item is not waiting for a cooldown
+    pub fn is_ready(&self, item: &Item) -> bool {
+        self.remaining_fraction(item) <= 0.0
+    }
+
+    /// Starts the cooldown of the item, if it has one
+    pub fn start(&mut self, item: &Item) {
+        if let (Some(key), Some(cooldown)) = (item.cooldown_key(), &item.cooldown) {
+            self.timers.insert(key, Timer::from_seconds(cooldown.duration, TimerMode::Once));
+        }
+    }
+
+    /// Fraction of the cooldown left for this item, from 1.0 (just used) to 0.0 (ready)
+    pub fn remaining_fraction(&self, item: &Item) -> f32 {
+        item.cooldown_key()
+            .and_then(|key| self.timers.get(&key))
+            .map(|timer| 1.0 - timer.fraction())
+            .unwrap_or(0.0)
+    }
+
+    pub fn tick(&mut self, delta: std::time::Duration) {
+        self.timers.retain(|_, timer| !timer.tick(delta).finished());
+    }
+}
+
+/// Present on a player while they are channeling an item.
+/// Moving or losing health cancels the channel.
+#[derive(Component)]
+pub struct ItemChannel {
+    pub slot: usize,
+    pub timer: Timer,
+    pub start_position: Vec3,
+    pub start_health: f32,
 }
 
 #[derive(Component, Clone)]
@@ -80,13 +301,252 @@ pub struct Weapon {
     pub name: String,
     pub description: String,
     pub throwable: bool,
-    pub durability: u16
+    pub durability: u16,
+    /// Damage dealt by a hit with this weapon
+    pub damage: f32,
+    pub damage_type: DamageType,
+    /// Strength of the push a hit gives
+    pub knockback: f32,
+    /// Seconds a hit player can't act, zero for none
+    pub hit_stun: f32,
+    /// The loadout slot this weapon prefers
+    pub slot: WeaponSlotKind,
+    /// Present if the weapon fires ammunition instead of being swung
+    pub ranged: Option<RangedWeapon>,
 }
 
 impl Weapon {
     pub fn decrement_durability(&mut self) {
         self.durability = self.durability.saturating_sub(1);
     }
+
+    /// The number shown on the weapon slot: ammo in the magazine for ranged weapons,
+    /// durability for everything else
+    pub fn slot_counter(&self) -> u16 {
+        self.ranged
+            .as_ref()
+            .map(|ranged| ranged.magazine)
+            .unwrap_or(self.durability)
+    }
+
+    /// Wraps the weapon back into an item, e.g. to drop it into the world
+    pub fn into_item(self) -> Item {
+        Item {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            type_: ItemType::Weapon,
+            effect: ItemEffect::WeaponItem(self),
+            use_time: 0.0,
+            cooldown: None,
+        }
+    }
+}
+
+/// The kinds of ammunition used by ranged weapons
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AmmoType {
+    Pebble,
+    Pellet,
+}
+
+/// How a ranged weapon delivers its shots
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FireMode {
+    /// Hits instantly along the aim direction, up to `range`
+    Hitscan { range: f32 },
+    /// Fires a physical projectile that drops with gravity
+    Projectile { speed: f32, gravity_scale: f32 },
+}
+
+#[derive(Debug, Clone)]
+pub struct RangedWeapon {
+    pub fire_mode: FireMode,
+    pub ammo_type: AmmoType,
+    pub magazine_size: u16,
+    /// Shots left in the magazine
+    pub magazine: u16,
+    pub reserve_ammo: u16,
+    /// Seconds it takes to reload
+    pub reload_time: f32,
+    /// Shots per second
+    pub fire_rate: f32,
+    /// Maximum angle in radians a shot can deviate from the aim direction
+    pub spread: f32,
+    /// Seconds until the weapon can fire again
+    pub fire_cooldown: f32,
+}
+
+impl RangedWeapon {
+    pub fn can_fire(&self) -> bool {
+        self.magazine > 0 && self.fire_cooldown <= 0.0
+    }
+
+    pub fn can_reload(&self) -> bool {
+        self.magazine < self.magazine_size && self.reserve_ammo > 0
+    }
+
+    /// Uses one shot from the magazine and starts the fire cooldown
+    pub fn consume_shot(&mut self) {
+        self.magazine = self.magazine.saturating_sub(1);
+        self.fire_cooldown = 1.0 / self.fire_rate;
+    }
+
+    /// Moves ammo from the reserve into the magazine
+    pub fn reload(&mut self) {
+        let loaded = (self.magazine_size - self.magazine).min(self.reserve_ammo);
+        self.magazine += loaded;
+        self.reserve_ammo -= loaded;
+    }
+}
+
+/// Present on a player while the held ranged weapon is being reloaded
+#[derive(Component)]
+pub struct Reloading {
+    pub timer: Timer,
+}
+
+/// The slots of a [`WeaponLoadout`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WeaponSlotKind {
+    #[default]
+    Primary,
+    Secondary,
+    Melee,
+}
+
+impl WeaponSlotKind {
+    pub const ALL: [WeaponSlotKind; 3] = [
+        WeaponSlotKind::Primary,
+        WeaponSlotKind::Secondary,
+        WeaponSlotKind::Melee,
+    ];
+
+    pub fn index(self) -> usize {
+        match self {
+            WeaponSlotKind::Primary => 0,
+            WeaponSlotKind::Secondary => 1,
+            WeaponSlotKind::Melee => 2,
+        }
+    }
+}
+
+/// The weapons carried by a player, one per [`WeaponSlotKind`]
+#[derive(Default)]
+pub struct WeaponLoadout {
+    slots: [Option<Weapon>; 3],
+    pub active: WeaponSlotKind,
+}
+
+impl WeaponLoadout {
+    pub fn get(&self, slot: WeaponSlotKind) -> Option<&Weapon> {
+        self.slots[slot.index()].as_ref()
+    }
+
+    pub fn get_mut(&mut self, slot: WeaponSlotKind) -> Option<&mut Weapon> {
+        self.slots[slot.index()].as_mut()
+    }
+
+    pub fn take(&mut self, slot: WeaponSlotKind) -> Option<Weapon> {
+        self.slots[slot.index()].take()
+    }
+
+    pub fn active_weapon(&self) -> Option<&Weapon> {
+        self.get(self.active)
+    }
+
+    pub fn active_weapon_mut(&mut self) -> Option<&mut Weapon> {
+        self.get_mut(self.active)
+    }
+
+    pub fn weapons(&self) -> impl Iterator<Item = &Weapon> {
+        self.slots.iter().flatten()
+    }
+
+    pub fn weapons_mut(&mut self) -> impl Iterator<Item = &mut Weapon> {
+        self.slots.iter_mut().flatten()
+    }
+
+    /// Adds ammo to the reserve of the first weapon that uses it.
+    /// Returns false if no weapon in the loadout uses this ammo.
+    pub fn add_ammo(&mut self, ammo_type: AmmoType, amount: u16) -> bool {
+        let ranged = self
+            .weapons_mut()
+            .filter_map(|weapon| weapon.ranged.as_mut())
+            .find(|ranged| ranged.ammo_type == ammo_type);
+        match ranged {
+            Some(ranged) => {
+                ranged.reserve_ammo = ranged.reserve_ammo.saturating_add(amount);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Puts the weapon into the loadout.
+    /// Returns the weapon it replaced if the matching slots were already full.
+    pub fn equip(&mut self, weapon: Weapon) -> Option<Weapon> {
+        let slot = self.slot_for(&weapon);
+        self.slots[slot.index()].replace(weapon)
+    }
+
+    /// Picks the slot a new weapon goes into.
+    /// Primary and secondary weapons may use each other's slot when it is empty.
+    fn slot_for(&self, weapon: &Weapon) -> WeaponSlotKind {
+        let alternative = match weapon.slot {
+            WeaponSlotKind::Primary => WeaponSlotKind::Secondary,
+            WeaponSlotKind::Secondary => WeaponSlotKind::Primary,
+            WeaponSlotKind::Melee => return WeaponSlotKind::Melee,
+        };
+
+        if self.get(weapon.slot).is_none() {
+            weapon.slot
+        } else if self.get(alternative).is_none() {
+            alternative
+        } else if self.active == alternative {
+            // Both are full, swap out the weapon that is currently held
+            alternative
+        } else {
+            weapon.slot
+        }
+    }
+}
+
+/// A weapon that was thrown and is flying through the world.
+/// It lands as a pickup that keeps its remaining durability.
+#[derive(Component)]
+pub struct ThrownWeapon {
+    pub weapon: Weapon,
+}
+
+/// A projectile fired from a ranged weapon, it breaks on the first impact
+#[derive(Component)]
+pub struct Bullet;
+
+/// Present on a player while they are switching weapons.
+/// The new weapon can't be used until the timer finishes.
+#[derive(Component)]
+pub struct WeaponSwitch {
+    pub timer: Timer,
+}
+
+/// What came of using an item
+pub enum ItemUse {
+    /// The item is still in its slot, nothing happened
+    Kept,
+    /// The item was used up
+    Consumed,
+    /// The item was used up by throwing it, the system that used it spawns the throw
+    Thrown(ItemThrow),
+}
+
+/// An object thrown by using an item, the system that used the item spawns it
+#[derive(Clone)]
+pub struct ItemThrow {
+    pub params: ThrowParams,
+    pub source: DamageSource,
+    pub origin: Vec3,
+    pub direction: Vec3,
+    pub fuse: Option<FuseSpec>,
 }
 
 /// Define the Inventory component with exactly 5 slots.
@@ -94,7 +554,7 @@ impl Weapon {
 pub struct Inventory {
     /// Each slot can hold an item or be empty.
     pub slots: [Option<Item>; 5],
-    pub weapon: Option<Weapon>,
+    pub weapons: WeaponLoadout,
     pub current_selected_item: usize,
 }
 
@@ -104,7 +564,7 @@ impl Inventory {
         Self {
             slots: [None, None, None, None, None],
             current_selected_item: 0,
-            weapon: None
+            weapons: WeaponLoadout::default(),
         }
     }
 
@@ -120,6 +580,24 @@ impl Inventory {
         Err(item)
     }
 
+    /// Returns true if there is at least one empty slot
+    pub fn has_space(&self) -> bool {
+        self.slots.iter().any(Option::is_none)
+    }
+
+    /// Sums the modifiers of every passive item held in the inventory
+    pub fn passive_modifiers(&self) -> StatModifier {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|item| item.type_ == ItemType::Passive)
+            .filter_map(|item| match &item.effect {
+                ItemEffect::Passive(modifier) => Some(modifier),
+                _ => None,
+            })
+            .fold(StatModifier::default(), |total, modifier| total.combine(modifier))
+    }
+
     /// Removes an item from a specific slot by index.
     /// Returns the removed item, or None if the slot was empty or index is invalid.
     pub fn remove_item(&mut self, index: usize) -> Option<Item> {
@@ -130,79 +608,265 @@ impl Inventory {
         }
     }
 
-    /// Use the selected item and remove them
+    /// Returns the currently selected item, if any
+    pub fn selected_item(&self) -> Option<&Item> {
+        self.slots[self.current_selected_item].as_ref()
+    }
+
+    /// Use the selected item and remove them, returns what the item throws if it is a throwable
     pub fn use_item(&mut self,
                     player: &mut PlayerStats,
-                    transform: Option<Mut<Transform>>,
+                    transform: Option<&Transform>,
                     direction: Option<&Direction>,
                     commands: &mut Commands,
-                    entity: Entity,
-                    time: &Res<Time>) {
-        if let Some(item) = self.slots[self.current_selected_item].take() {
+                    user: &Instigator) -> ItemUse {
+        self.use_item_at(self.current_selected_item, player, transform, direction, commands, user)
+    }
+
+    /// Use the item in the given slot and remove it, returns whether it was used up or thrown
+    pub fn use_item_at(&mut self,
+                       slot: usize,
+                       player: &mut PlayerStats,
+                       transform: Option<&Transform>,
+                       direction: Option<&Direction>,
+                       commands: &mut Commands,
+                       user: &Instigator) -> ItemUse {
+        if let Some(Some(item)) = self.slots.get(slot) {
+            // Passive items work while held and are never consumed
+            if item.type_ == ItemType::Passive {
+                return ItemUse::Kept;
+            }
+            // Throwables are kept when there is nowhere to throw them from
+            if matches!(item.effect, ItemEffect::Throw { .. }) && (direction.is_none() || transform.is_none()) {
+                return ItemUse::Kept;
+            }
+        }
+
+        if let Some(item) = self.slots.get_mut(slot).and_then(Option::take) {
             match item.effect {
                 ItemEffect::IncreaseSpeed { amount, duration } => {
 
                     player.speed += amount;
-                    println!("Speed increased by {}", amount);
+                    commands.send_event(NotificationEvent::new(
+                        user.entity,
+                        format!("Speed increased by {}", amount),
+                        NotificationPriority::Low,
+                    ));
 
                     // Add a SpeedBoost component to track the duration
-                    commands.entity(entity).insert(SpeedBoost {
+                    commands.entity(user.entity).insert(SpeedBoost {
                         amount,
                         timer: Timer::from_seconds(duration, TimerMode::Once),
                     });
                 }
                 ItemEffect::Heal(amount) => {
-                    player.health += amount;
-                    println!("Healed by {}", amount);
+                    player.health = (player.health + amount).min(player.max_health);
+                    commands.send_event(NotificationEvent::new(
+                        user.entity,
+                        format!("Healed by {}", amount),
+                        NotificationPriority::Low,
+                    ));
                 }
-                ItemEffect::Throw(
-                                  handle_mesh,
-                                  handle_material
-                ) => {
-                    let Some(direction) = direction else {
-                        return;
-                    };
-                    let Some(origin) = transform else {
-                        return;
+                ItemEffect::Armor { amount, absorption } => {
+                    player.add_armor(amount, absorption);
+                    commands.send_event(NotificationEvent::new(
+                        user.entity,
+                        format!("Armor restored by {}", amount),
+                        NotificationPriority::Low,
+                    ));
+                }
+                ItemEffect::Throw { mesh, material, damage, speed, fuse } => {
+                    // Both were checked before taking the item
+                    let (Some(direction), Some(origin)) = (direction, transform) else {
+                        return ItemUse::Kept;
                     };
-                    println!("Item thrown in direction {:?}", direction.direction);
-                    // spawn thrown object
-                    let speed = 30f32;
-                    commands.spawn(
-                        (
-                                Velocity {
-                                    linvel: speed * direction.direction,
-                                        ..Default::default()
-                                },
-                                CollisionGroups::new(
-                                    Group::GROUP_3,
-                                    Group::GROUP_1 | Group::GROUP_2,
-                                ),
-                                ActiveEvents::COLLISION_EVENTS,
-                                ThrewObject {
-                                    spawn_time: time.elapsed_secs()
-                                },
-                                RigidBody::Dynamic,
-                                GravityScale(1.0),
-                                Transform::from_translation(origin.translation + Vec3::from((direction.direction.x, 1., direction.direction.z))),
-                                Collider::ball(0.5),
-                                Mesh3d(handle_mesh),
-                                MeshMaterial3d(handle_material),
-                                Sensor
-                            )
-                    );
-
+                    let params = ThrowParams::new(mesh, material, speed)
+                        .with_damage(damage)
+                        .with_knockback(4.0)
+                        .with_restitution(0.5);
+                    return ItemUse::Thrown(ItemThrow {
+                        params,
+                        source: DamageSource::new(user.clone(), &item.name),
+                        origin: origin.translation,
+                        direction: direction.direction,
+                        fuse,
+                    });
                 }
                 ItemEffect::WeaponItem(weapon) => {
-                    // insert this bruh to weapon slot
-                    self.weapon = Some(weapon);
+                    // insert this bruh to weapon slot, the replaced weapon takes its place
+                    if let Some(replaced) = self.weapons.equip(weapon) {
+                        self.slots[slot] = Some(replaced.into_item());
+                    }
+                }
+                ItemEffect::Passive(_) => {}
+                ItemEffect::Ammo { ammo_type, amount } => {
+                    if self.weapons.add_ammo(ammo_type, amount) {
+                        commands.send_event(NotificationEvent::new(
+                            user.entity,
+                            format!("Added {} {:?} ammo", amount, ammo_type),
+                            NotificationPriority::Low,
+                        ));
+                    } else {
+                        // Nothing to load it into, keep the ammo
+                        commands.send_event(NotificationEvent::new(
+                            user.entity,
+                            format!("No weapon uses {:?} ammo", ammo_type),
+                            NotificationPriority::Normal,
+                        ));
+                        self.slots[slot] = Some(item);
+                        return ItemUse::Kept;
+                    }
                 }
             }
+            return ItemUse::Consumed;
         }
+        ItemUse::Kept
     }
 
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weapon(name: &str, slot: WeaponSlotKind) -> Weapon {
+        Weapon {
+            name: name.to_string(),
+            description: String::new(),
+            throwable: false,
+            durability: 10,
+            damage: 10.0,
+            damage_type: DamageType::Physical,
+            knockback: 0.0,
+            hit_stun: 0.0,
+            slot,
+            ranged: None,
+        }
+    }
+
+    /// A loadout with the given weapons equipped in order
+    fn loadout(weapons: &[(&str, WeaponSlotKind)]) -> WeaponLoadout {
+        let mut loadout = WeaponLoadout::default();
+        for (name, slot) in weapons {
+            loadout.equip(weapon(name, *slot));
+        }
+        loadout
+    }
+
+    fn name_in(loadout: &WeaponLoadout, slot: WeaponSlotKind) -> Option<&str> {
+        loadout.get(slot).map(|weapon| weapon.name.as_str())
+    }
+
+    #[test]
+    fn armor_takes_its_share_of_a_hit() {
+        let mut stats = PlayerStats::default();
+        stats.add_armor(50.0, 0.6);
+
+        assert_eq!(stats.absorb_with_armor(20.0), 8.0);
+        assert_eq!(stats.armor, 38.0);
+    }
+
+    #[test]
+    fn damage_goes_through_once_the_armor_runs_out() {
+        let mut stats = PlayerStats::default();
+        stats.add_armor(5.0, 1.0);
+
+        assert_eq!(stats.absorb_with_armor(20.0), 15.0);
+        assert_eq!(stats.armor, 0.0);
+        assert_eq!(stats.absorb_with_armor(10.0), 10.0);
+    }
+
+    #[test]
+    fn weaker_armor_tops_up_without_downgrading() {
+        let mut stats = PlayerStats::default();
+        stats.add_armor(40.0, 0.8);
+        stats.add_armor(80.0, 0.3);
+
+        assert_eq!(stats.armor_absorption, 0.8);
+        assert_eq!(stats.armor, stats.max_armor);
 
+        stats.add_armor(10.0, 0.9);
+        assert_eq!(stats.armor_absorption, 0.9);
+    }
+
+    #[test]
+    fn depleted_armor_takes_the_absorption_of_the_new_armor() {
+        let mut stats = PlayerStats::default();
+        stats.add_armor(10.0, 0.8);
+        stats.absorb_with_armor(100.0);
+        assert_eq!(stats.armor, 0.0);
 
+        stats.add_armor(30.0, 0.3);
 
+        assert_eq!(stats.armor_absorption, 0.3);
+        assert_eq!(stats.armor, 30.0);
+    }
+
+    #[test]
+    fn weapons_go_into_their_own_slot_first() {
+        let mut loadout = WeaponLoadout::default();
+
+        assert!(loadout.equip(weapon("Pistol", WeaponSlotKind::Secondary)).is_none());
+        assert!(loadout.equip(weapon("Rifle", WeaponSlotKind::Primary)).is_none());
+        assert!(loadout.equip(weapon("Sword", WeaponSlotKind::Melee)).is_none());
+
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Primary), Some("Rifle"));
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Secondary), Some("Pistol"));
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Melee), Some("Sword"));
+    }
+
+    #[test]
+    fn primary_weapons_take_an_empty_secondary_slot() {
+        let mut loadout = loadout(&[("Rifle", WeaponSlotKind::Primary)]);
+
+        assert_eq!(loadout.slot_for(&weapon("Shotgun", WeaponSlotKind::Primary)), WeaponSlotKind::Secondary);
+        assert!(loadout.equip(weapon("Shotgun", WeaponSlotKind::Primary)).is_none());
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Primary), Some("Rifle"));
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Secondary), Some("Shotgun"));
+    }
+
+    #[test]
+    fn secondary_weapons_take_an_empty_primary_slot() {
+        let mut loadout = loadout(&[("Pistol", WeaponSlotKind::Secondary)]);
+
+        assert_eq!(loadout.slot_for(&weapon("Revolver", WeaponSlotKind::Secondary)), WeaponSlotKind::Primary);
+        assert!(loadout.equip(weapon("Revolver", WeaponSlotKind::Secondary)).is_none());
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Primary), Some("Revolver"));
+    }
+
+    #[test]
+    fn melee_weapons_only_use_the_melee_slot() {
+        let mut loadout = loadout(&[("Sword", WeaponSlotKind::Melee)]);
+
+        let replaced = loadout.equip(weapon("Axe", WeaponSlotKind::Melee));
+
+        assert_eq!(replaced.map(|weapon| weapon.name), Some(String::from("Sword")));
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Melee), Some("Axe"));
+        assert!(loadout.get(WeaponSlotKind::Primary).is_none());
+        assert!(loadout.get(WeaponSlotKind::Secondary).is_none());
+    }
+
+    #[test]
+    fn full_slots_swap_out_the_held_weapon() {
+        let mut loadout = loadout(&[("Rifle", WeaponSlotKind::Primary), ("Pistol", WeaponSlotKind::Secondary)]);
+        loadout.active = WeaponSlotKind::Secondary;
+
+        let replaced = loadout.equip(weapon("Shotgun", WeaponSlotKind::Primary));
+
+        assert_eq!(replaced.map(|weapon| weapon.name), Some(String::from("Pistol")));
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Primary), Some("Rifle"));
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Secondary), Some("Shotgun"));
+    }
+
+    #[test]
+    fn full_slots_replace_the_preferred_slot_otherwise() {
+        let mut loadout = loadout(&[("Rifle", WeaponSlotKind::Primary), ("Pistol", WeaponSlotKind::Secondary)]);
+        loadout.active = WeaponSlotKind::Melee;
+
+        let replaced = loadout.equip(weapon("Shotgun", WeaponSlotKind::Primary));
+
+        assert_eq!(replaced.map(|weapon| weapon.name), Some(String::from("Rifle")));
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Primary), Some("Shotgun"));
+        assert_eq!(name_in(&loadout, WeaponSlotKind::Secondary), Some("Pistol"));
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use bevy::prelude::*;
+
+/// Ticks per second the movement of players is simulated at, the same on every machine
+pub const MOVEMENT_TICK_RATE: f64 = 64.0;
+/// Most inputs kept waiting for the server, older ones are dropped when it falls further behind
+pub const MAX_PENDING_INPUTS: usize = 128;
+/// Prediction errors larger than this are snapped away at once instead of smoothed out
+pub const SNAP_DISTANCE: f32 = 2.0;
+/// Fraction of the remaining prediction error corrected every tick
+pub const CORRECTION_RATE: f32 = 0.2;
+
+/// One tick of movement input, kept until the server has acknowledged it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputFrame {
+    pub sequence: u32,
+    pub movement: Vec3,
+    pub jump: bool,
+    /// How far the player moved on the tick the input was applied
+    pub displacement: Vec3,
+}
+
+/**
+Predicts the movement of the local player without waiting for the server.
+Every tick the input is buffered with a sequence number, once the server acknowledges a sequence
+the player is rewound to the server's position and the inputs after it are replayed on top.
+
+The replay is an approximation: instead of simulating each input again against the physics world,
+it adds the displacement the input caused when it was predicted. That matches a real replay as long as
+the error is a small offset that doesn't change what the player bumps into, larger errors are snapped away.
+*/
+#[derive(Debug, Default, Component)]
+pub struct PredictedMovement {
+    pub next_sequence: u32,
+    /// Inputs the server hasn't acknowledged yet, oldest first
+    pub pending: VecDeque<InputFrame>,
+    pub acknowledged: Option<u32>,
+    /// Where the player was when the input of the current tick was recorded, the physics step moves them from there
+    pub last_position: Vec3,
+    /// Error between the predicted and the replayed position that is still being smoothed out
+    pub correction: Vec3,
+}
+
+impl PredictedMovement {
+    /// Buffers the input of a new tick and returns its sequence number
+    pub fn push(&mut self, movement: Vec3, jump: bool) -> u32 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        if self.pending.len() >= MAX_PENDING_INPUTS {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(InputFrame { sequence, movement, jump, displacement: Vec3::ZERO });
+        sequence
+    }
+
+    /**
+    Drops every input up to `sequence` and returns where the player is after replaying the rest
+    from `position`, `None` when the server state is not newer than the last one acknowledged.
+    */
+    pub fn acknowledge(&mut self, sequence: u32, position: Vec3) -> Option<Vec3> {
+        if self.acknowledged.is_some_and(|acknowledged| sequence <= acknowledged) {
+            return None;
+        }
+        self.acknowledged = Some(sequence);
+        while self.pending.front().is_some_and(|frame| frame.sequence <= sequence) {
+            self.pending.pop_front();
+        }
+        Some(position + self.pending.iter().map(|frame| frame.displacement).sum::<Vec3>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Buffers `count` inputs that each moved the player one unit along x
+    fn predicted(count: usize) -> PredictedMovement {
+        let mut prediction = PredictedMovement::default();
+        for _ in 0..count {
+            prediction.push(Vec3::X, false);
+            prediction.pending.back_mut().unwrap().displacement = Vec3::X;
+        }
+        prediction
+    }
+
+    fn pending_sequences(prediction: &PredictedMovement) -> Vec<u32> {
+        prediction.pending.iter().map(|frame| frame.sequence).collect()
+    }
+
+    #[test]
+    fn inputs_are_numbered_in_order() {
+        let mut prediction = PredictedMovement::default();
+        assert_eq!(prediction.push(Vec3::X, false), 0);
+        assert_eq!(prediction.push(Vec3::Z, true), 1);
+        assert_eq!(prediction.push(Vec3::ZERO, false), 2);
+
+        assert_eq!(pending_sequences(&prediction), vec![0, 1, 2]);
+        assert!(prediction.pending[1].jump);
+        assert_eq!(prediction.pending[1].movement, Vec3::Z);
+    }
+
+    #[test]
+    fn oldest_inputs_are_dropped_when_the_buffer_is_full() {
+        let prediction = predicted(MAX_PENDING_INPUTS + 2);
+
+        assert_eq!(prediction.pending.len(), MAX_PENDING_INPUTS);
+        assert_eq!(prediction.pending.front().unwrap().sequence, 2);
+        assert_eq!(prediction.next_sequence, MAX_PENDING_INPUTS as u32 + 2);
+    }
+
+    #[test]
+    fn acknowledging_replays_the_inputs_after_it() {
+        let mut prediction = predicted(5);
+
+        let replayed = prediction.acknowledge(1, Vec3::new(10.0, 0.0, 0.0));
+
+        // Inputs 2, 3 and 4 are replayed on top of the server position
+        assert_eq!(replayed, Some(Vec3::new(13.0, 0.0, 0.0)));
+        assert_eq!(pending_sequences(&prediction), vec![2, 3, 4]);
+        assert_eq!(prediction.acknowledged, Some(1));
+    }
+
+    #[test]
+    fn acknowledging_the_latest_input_leaves_nothing_pending() {
+        let mut prediction = predicted(3);
+
+        assert_eq!(prediction.acknowledge(2, Vec3::Y), Some(Vec3::Y));
+        assert!(prediction.pending.is_empty());
+    }
+
+    #[test]
+    fn stale_states_are_ignored() {
+        let mut prediction = predicted(5);
+        prediction.acknowledge(3, Vec3::ZERO);
+
+        assert_eq!(prediction.acknowledge(3, Vec3::Y), None);
+        assert_eq!(prediction.acknowledge(1, Vec3::Y), None);
+        assert_eq!(prediction.acknowledged, Some(3));
+        assert_eq!(pending_sequences(&prediction), vec![4]);
+    }
+
+    #[test]
+    fn states_can_skip_sequences() {
+        let mut prediction = predicted(6);
+        prediction.acknowledge(0, Vec3::ZERO);
+
+        // The server states for 1 to 3 got lost, 4 still acknowledges everything before it
+        assert_eq!(prediction.acknowledge(4, Vec3::ZERO), Some(Vec3::X));
+        assert_eq!(pending_sequences(&prediction), vec![5]);
+    }
+}
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+
+/// Points for each kill
+const KILL_POINTS: u32 = 100;
+/// Points for each assist
+const ASSIST_POINTS: u32 = 50;
+/// Points for each second holding an objective
+const OBJECTIVE_POINTS: u32 = 10;
+
+/**
+How a player is doing this match.
+Kept up to date from damage and death events by the score systems.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq, Component)]
+pub struct Score {
+    pub kills: u32,
+    pub deaths: u32,
+    /// Kills of other players this player helped with but didn't finish
+    pub assists: u32,
+    /// Kills since this player last died
+    pub streak: u32,
+    pub best_streak: u32,
+    /// Seconds spent holding objectives
+    pub objective: u32,
+}
+
+impl Score {
+    /// Score the scoreboard is sorted by
+    pub fn points(&self) -> u32 {
+        self.kills * KILL_POINTS + self.assists * ASSIST_POINTS + self.objective * OBJECTIVE_POINTS
+    }
+
+    pub fn record_kill(&mut self) {
+        self.kills += 1;
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+    }
+
+    pub fn record_death(&mut self) {
+        self.deaths += 1;
+        self.streak = 0;
+    }
+
+    pub fn record_assist(&mut self) {
+        self.assists += 1;
+    }
+
+    pub fn record_hold(&mut self) {
+        self.objective += 1;
+    }
+}
+
+/// Damage dealt to a player by another player, remembered to credit assists
+#[derive(Debug, Clone, Copy)]
+pub struct DamageContribution {
+    pub attacker: Entity,
+    pub amount: f32,
+    /// Game time the damage was dealt at
+    pub time: f32,
+}
+
+/// Damage a player took from others recently, cleared when they die
+#[derive(Debug, Default, Component)]
+pub struct RecentAttackers(pub Vec<DamageContribution>);
+
+impl RecentAttackers {
+    /// Seconds before a death that damage still counts towards an assist
+    pub const ASSIST_WINDOW: f32 = 10.0;
+    /// Damage needed to earn an assist, so a single graze doesn't count
+    pub const ASSIST_MIN_DAMAGE: f32 = 10.0;
+
+    pub fn record(&mut self, attacker: Entity, amount: f32, time: f32) {
+        self.0.retain(|contribution| time - contribution.time <= Self::ASSIST_WINDOW);
+        self.0.push(DamageContribution { attacker, amount, time });
+    }
+
+    /// Everyone who dealt enough damage to the player within the assist window before `time`
+    pub fn assisters(&self, time: f32) -> Vec<Entity> {
+        let mut totals: Vec<(Entity, f32)> = Vec::new();
+        for contribution in self.0.iter().filter(|contribution| time - contribution.time <= Self::ASSIST_WINDOW) {
+            match totals.iter_mut().find(|(attacker, _)| *attacker == contribution.attacker) {
+                Some((_, total)) => *total += contribution.amount,
+                None => totals.push((contribution.attacker, contribution.amount)),
+            }
+        }
+        totals
+            .into_iter()
+            .filter(|(_, total)| *total >= Self::ASSIST_MIN_DAMAGE)
+            .map(|(attacker, _)| attacker)
+            .collect()
+    }
+}
@@ -0,0 +1,32 @@
+use bevy::color::palettes::tailwind::{BLUE_500, RED_500};
+use bevy::prelude::*;
+
+/// Side a player fights on in team modes, players without a team fight everyone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub enum Team {
+    Red,
+    Blue,
+}
+
+impl Team {
+    pub const ALL: [Team; 2] = [Team::Red, Team::Blue];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Team::Red => "Red",
+            Team::Blue => "Blue",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Team::Red => Color::from(RED_500),
+            Team::Blue => Color::from(BLUE_500),
+        }
+    }
+
+    /// Whether two players are on the same team, players without a team have no allies
+    pub fn are_allies(a: Option<&Team>, b: Option<&Team>) -> bool {
+        matches!((a, b), (Some(a), Some(b)) if a == b)
+    }
+}
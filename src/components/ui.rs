@@ -1,4 +1,6 @@
-use bevy::prelude::Component;
+use bevy::prelude::{Component, Entity, Timer, Vec3};
+use crate::components::player::WeaponSlotKind;
+use crate::events::notification::NotificationPriority;
 
 #[derive(Debug)]
 pub enum Info {
@@ -29,11 +31,190 @@ pub struct InventorySlot {
 #[derive(Component)]
 pub struct InventorySlotImage;
 
+/// Marker for a weapon bar slot UI node with its loadout slot.
 #[derive(Component)]
-pub struct WeaponSlot;
+pub struct WeaponSlot {
+    pub slot: WeaponSlotKind,
+}
+
+/// Marker for the weapon image inserted into a weapon slot, with the weapon it shows.
+#[derive(Component)]
+pub struct WeaponSlotImage {
+    pub weapon: String,
+}
+
+#[derive(Component)]
+pub struct WeaponDurabilityText {
+    pub slot: WeaponSlotKind,
+}
+
+/// Marker for the text showing the ammo of the held ranged weapon
+#[derive(Component)]
+pub struct AmmoText;
+
+/// Marker for the progress bar shown while channeling an item.
+#[derive(Component)]
+pub struct ItemChannelBar;
+
+/// Marker for the fill of the item channel progress bar.
+#[derive(Component)]
+pub struct ItemChannelBarFill;
+
+/// Overlay on an inventory slot that shrinks as the item's cooldown runs out.
+#[derive(Component)]
+pub struct InventorySlotCooldown {
+    pub slot: usize,
+}
+
+/// A player stat shown as a bar on the HUD
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Vital {
+    Health,
+    Armor,
+}
+
+/// Marker for the fill of a vital bar.
+#[derive(Component)]
+pub struct VitalBarFill {
+    pub vital: Vital,
+}
+
+/// Marker for the text on a vital bar.
+#[derive(Component)]
+pub struct VitalText {
+    pub vital: Vital,
+}
+
+/// Red screen edge that flashes when the player is hurt and stays while their health is low.
+#[derive(Component, Default)]
+pub struct DamageVignette {
+    /// Strength from low health, between 0 and 1
+    pub low_health: f32,
+    /// Strength of the flash from the last hit, fading to 0
+    pub flash: f32,
+}
+
+/// Marker for the point at the center of the screen that damage indicators are placed around.
+#[derive(Component)]
+pub struct DamageIndicatorAnchor;
+
+/// Mark around the crosshair pointing towards where the player was hit from.
+#[derive(Component)]
+pub struct DamageIndicator {
+    /// The player who dealt the damage, followed while they are alive
+    pub attacker: Option<Entity>,
+    /// Last known world position the damage came from
+    pub source: Vec3,
+    pub timer: Timer,
+}
+
+/// Marker around the crosshair shown when the player's attacks land.
+#[derive(Component)]
+pub struct HitMarker {
+    pub timer: Timer,
+    /// Whether the last hit was a kill
+    pub kill: bool,
+}
+
+/// Marker for the dots of the hit marker.
+#[derive(Component)]
+pub struct HitMarkerDot;
+
+/// What a world anchored UI node follows
+#[derive(Debug, Clone, Copy)]
+pub enum AnchorTarget {
+    /// Position of an entity, the node is hidden once the entity is gone
+    Entity(Entity),
+    Point(Vec3),
+}
+
+/**
+UI node kept at the screen position of a point in the world, as seen through the player camera.
+It is hidden while the point is behind the camera, too far away or, if occludable, behind structures.
+*/
+#[derive(Component)]
+pub struct WorldAnchor {
+    pub target: AnchorTarget,
+    /// Added to the target position, in world units
+    pub offset: Vec3,
+    pub max_distance: f32,
+    pub occludable: bool,
+}
+
+/// Name and health bar floating above another player.
+#[derive(Component)]
+pub struct Nameplate {
+    pub player: Entity,
+}
+
+/// Marker for the fill of a nameplate health bar.
+#[derive(Component)]
+pub struct NameplateHealthFill {
+    pub player: Entity,
+}
+
+/// Damage dealt by the local player, rising from where it landed and fading out.
+#[derive(Component)]
+pub struct FloatingDamageNumber {
+    pub timer: Timer,
+}
+
+/// Marker for the list that notifications are shown in.
+#[derive(Component)]
+pub struct NotificationList;
+
+/// A message shown to the local player, repeated messages are stacked into one.
+#[derive(Component)]
+pub struct Toast {
+    pub message: String,
+    pub priority: NotificationPriority,
+    /// How many times the message was sent while it was on screen
+    pub count: u32,
+    pub timer: Timer,
+}
+
+/// Marker for the kill feed in the corner of the screen.
+#[derive(Component)]
+pub struct KillFeed;
+
+/// A kill shown in the kill feed.
+#[derive(Component)]
+pub struct KillFeedEntry {
+    pub timer: Timer,
+}
+
+/// Marker for the scoreboard overlay shown while Tab is held.
+#[derive(Component)]
+pub struct Scoreboard;
+
+/// Marker for the grid holding one row per player on the scoreboard.
+#[derive(Component)]
+pub struct ScoreboardRows;
+
+/// Marker for the text at the top of the screen showing the match clock.
+#[derive(Component)]
+pub struct MatchTimerText;
+
+/// Marker for the text under the match clock counting down to the next shrink of the safe zone.
+#[derive(Component)]
+pub struct SafeZoneText;
+
+/// Marker for the bar at the top of the screen showing the state of the hill.
+#[derive(Component)]
+pub struct CaptureBar;
+
+/// Marker for the fill of the hill bar, showing capture progress or the owner's hold time.
+#[derive(Component)]
+pub struct CaptureBarFill;
+
+/// Marker for the text above the hill bar saying who holds the hill.
+#[derive(Component)]
+pub struct CaptureText;
 
+/// Marker for the full screen overlay of the menu, lobby and post-match summary.
 #[derive(Component)]
-pub struct WeaponSlotImage;
+pub struct MatchOverlay;
 
+/// Marker for the text on the match overlay.
 #[derive(Component)]
-pub struct WeaponDurabilityText;
\ No newline at end of file
+pub struct MatchOverlayText;
@@ -1,5 +1,14 @@
 use std::ops::Mul;
 use bevy::prelude::*;
+use bevy_rapier3d::prelude::Group;
+
+use crate::components::combat::DamageType;
+use crate::components::team::Team;
+
+/// Collision group of the static world: the ground, walls and structures
+pub const WORLD_GROUP: Group = Group::GROUP_4;
+/// Collision group of trigger volumes, they only detect players
+pub const TRIGGER_GROUP: Group = Group::GROUP_5;
 
 #[derive(Debug, Component)]
 pub struct Ground;
@@ -14,6 +23,10 @@ impl EntityName {
     pub fn new(name: &str) -> Self {
         Self(String::from(name))
     }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
 }
 
 /**
@@ -36,11 +49,29 @@ pub struct AabbCollider {
     pub half_extents: Vec3,
 }
 
+/**
+An object thrown or fired into the world.
+It bounces off the world and hits players, but never its thrower.
+*/
 #[derive(Component)]
 pub struct ThrewObject {
     pub spawn_time: f32, // Stores the time the object was spawned
+    pub damage: f32,
+    pub damage_type: DamageType,
+    /// Strength of the push given to a player it hits
+    pub knockback: f32,
+    pub hit_stun: f32,
+    /// Only the first player it hits takes damage
+    pub has_hit: bool,
 }
 
+/// Seconds a thrown object passes through players without pushing them, so it can't shove its own thrower
+pub const THROWER_GRACE_TIME: f32 = 0.15;
+
+/// Marks a thrown object that still passes through players, hits are still detected
+#[derive(Component)]
+pub struct ClearingThrower;
+
 // pub fn ray_intersect_aabb(
 //     ray_origin: Vec3,
 //     ray_direction: Vec3,
@@ -163,3 +194,30 @@ pub fn multi_ray_intersect_from_box(
     closest_intersection
 }
 
+
+/// A place players can be spawned at, players face the way its transform does
+#[derive(Debug, Component)]
+pub struct SpawnPoint {
+    /// In team modes only this team spawns here, `None` for anyone
+    pub team: Option<Team>,
+}
+
+/// Marker for the wall showing the edge of the safe zone
+#[derive(Debug, Component)]
+pub struct SafeZoneBoundary;
+
+/// Present on a player outside the safe zone, the zone hurts more the longer they stay out
+#[derive(Debug, Default, Component)]
+pub struct OutsideZone {
+    /// Damage ticks taken since leaving the zone
+    pub ticks: u32,
+}
+
+/**
+A sensor area that keeps track of what is inside it.
+Needs a sensor collider, entities are added and removed as Rapier reports them entering and leaving.
+*/
+#[derive(Debug, Default, Component)]
+pub struct TriggerVolume {
+    pub inside: Vec<Entity>,
+}
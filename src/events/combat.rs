@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::components::combat::{DamageSource, DamageType};
+
+/// Sent when something should take damage.
+/// The damage is reduced by the target's resistances before it is applied.
+#[derive(Debug, Event)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub damage_type: DamageType,
+    /// Impulse pushing the target away from the hit
+    pub knockback: Vec3,
+    /// Seconds the target can't act, zero for none
+    pub hit_stun: f32,
+    /// Where the hit came from, used to tell if it can be blocked
+    pub origin: Vec3,
+    /// Where the hit landed on the target
+    pub point: Vec3,
+    pub source: DamageSource,
+}
+
+/**
+Sent after a damage event was applied to a player, with the damage that went through.
+The HUD reacts to these instead of watching health.
+*/
+#[derive(Debug, Event)]
+pub struct DamageTakenEvent {
+    pub target: Entity,
+    /// Damage after blocking, resistance and armor, zero for parried hits
+    pub amount: f32,
+    pub origin: Vec3,
+    pub point: Vec3,
+    pub source: DamageSource,
+    /// Whether this hit was the final blow
+    pub lethal: bool,
+}
+
+/// Sent when a player swings, fires or throws something, whether or not it hits
+#[derive(Debug, Event)]
+pub struct AttackEvent {
+    pub attacker: Entity,
+}
+
+/// Sent when a player's health drops to zero
+#[derive(Debug, Event)]
+pub struct DeathEvent {
+    pub victim: Entity,
+    pub victim_name: String,
+    /// What dealt the final blow, the instigator gets the kill
+    pub source: DamageSource,
+}
+
+/// Sent when a thrown or fired object hits a player or the world
+#[derive(Debug, Event)]
+pub struct ProjectileImpactEvent {
+    pub projectile: Entity,
+    pub hit: Entity,
+    /// World position of the contact
+    pub point: Vec3,
+    /// Surface normal at the contact, pointing towards the projectile
+    pub normal: Vec3,
+}
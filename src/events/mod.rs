@@ -0,0 +1,3 @@
+pub mod combat;
+pub mod notification;
+pub mod network;
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+/**
+Sent when the server's state of a player arrives, after it applied the input with `sequence`.
+Nothing sends it until the game is networked, the local player then reconciles its prediction with it.
+*/
+#[derive(Debug, Event)]
+pub struct AuthoritativeStateEvent {
+    pub player: Entity,
+    pub sequence: u32,
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+/// How important a notification is.
+/// More important ones stay on screen longer and push out less important ones when the list is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NotificationPriority {
+    Low,
+    Normal,
+    High,
+}
+
+impl NotificationPriority {
+    /// Seconds a notification of this priority stays on screen
+    pub fn lifetime(&self) -> f32 {
+        match self {
+            NotificationPriority::Low => 2.0,
+            NotificationPriority::Normal => 3.0,
+            NotificationPriority::High => 5.0,
+        }
+    }
+}
+
+/// Sent to show a message to a player, only messages for the local player are shown
+#[derive(Debug, Event)]
+pub struct NotificationEvent {
+    pub player: Entity,
+    pub message: String,
+    pub priority: NotificationPriority,
+}
+
+impl NotificationEvent {
+    pub fn new(player: Entity, message: impl Into<String>, priority: NotificationPriority) -> Self {
+        Self {
+            player,
+            message: message.into(),
+            priority,
+        }
+    }
+}
@@ -1,20 +1,69 @@
 mod components;
+mod events;
 mod resources;
 mod spawns;
 mod systems;
 
 use crate::resources::camera::CameraState;
-use crate::resources::game::{GameState, WorldAttribute};
+use crate::resources::game::{DamageRules, GameState, MatchClock, MatchSettings, WorldAttribute};
+use crate::resources::item::ItemAssets;
+use crate::resources::launch::LaunchOptions;
+use crate::resources::navigation::{NavDebug, NavGrid};
+use crate::resources::rng::GameRng;
+use crate::resources::rules::{MatchRules, MATCH_RULES_PATH};
+use crate::resources::team::TeamAssets;
+use crate::resources::zone::SafeZone;
 use crate::resources::*;
+use crate::components::prediction::MOVEMENT_TICK_RATE;
 use crate::spawns::ground::spawn_ground;
 use crate::spawns::light::spawn_light;
 use crate::spawns::player::spawn_player;
+use crate::spawns::spawn_point::spawn_spawn_points;
+use crate::spawns::zone::spawn_safe_zone_boundary;
+use crate::spawns::objective::spawn_capture_zone;
 use crate::spawns::ui::{setup_debug_ui, setup_game_ui};
+use crate::events::combat::{AttackEvent, DamageEvent, DamageTakenEvent, DeathEvent, ProjectileImpactEvent};
+use crate::events::network::AuthoritativeStateEvent;
+use crate::events::notification::NotificationEvent;
+use crate::systems::bot::{bot_act_system, bot_think_system, fill_bot_slots_system};
+use crate::systems::prediction::{reconcile_system, record_displacement_system, record_input_system};
+use crate::systems::navigation::{draw_nav_debug_system, toggle_nav_debug_system, update_nav_grid_system};
+use crate::systems::area::{area_effect_system, fuse_system, status_effect_system};
 use crate::systems::camera::*;
+use crate::systems::combat::{apply_damage_system, armor_regen_system, melee_swing_system, stagger_system};
+use crate::systems::game::{
+    apply_match_rules_system, gameplay_running, lobby_system, main_menu_system, match_end_system, post_match_system,
+    reload_match_rules_system, reset_arena_system, start_match_system, start_post_match_system, start_warmup_system,
+    tune_items_system, warmup_system,
+};
 use crate::systems::player::*;
-use crate::systems::ui::{update_durability_text_system, update_inventory_ui_system, update_player_info_system};
+use crate::systems::spawn::{respawn_system, spawn_protection_system, start_respawn_system};
+use crate::systems::projectile::{
+    projectile_collision_system, projectile_impact_system, projectile_timeout_system, thrower_clearance_system,
+};
+use crate::systems::weapon::{fire_weapon_system, reload_input_system, reload_system, weapon_cooldown_system};
+use crate::systems::score::{record_damage_system, score_system};
+use crate::systems::team::{assign_team_system, balance_teams_system, team_color_system};
+use crate::systems::objective::{
+    capture_zone_system, capture_zone_visual_system, reset_capture_zone_system, rotate_capture_zone_system,
+    trigger_volume_system,
+};
+use crate::systems::zone::{reset_safe_zone_system, safe_zone_system, zone_boundary_system, zone_damage_system};
+use crate::systems::ui::{
+    damage_vignette_system, hit_marker_system, kill_feed_system, notification_system, scoreboard_system,
+    spawn_damage_indicator_system, spawn_damage_number_system, spawn_nameplate_system,
+    update_ammo_text_system, update_damage_indicator_system, update_damage_number_system,
+    update_durability_text_system, update_inventory_ui_system, update_item_progress_ui_system,
+    update_match_ui_system, update_nameplate_health_system, update_player_info_system,
+    update_capture_ui_system, update_vitals_ui_system, update_zone_ui_system, world_anchor_system,
+};
 use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
+use bevy::hierarchy::HierarchyPlugin;
+use bevy::input::InputPlugin;
+use bevy::log::LogPlugin;
 use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::state::app::StatesPlugin;
 use bevy_rapier3d::prelude::*;
 use bevy_rapier3d::render::RapierDebugRenderPlugin;
 use std::time::Duration;
@@ -37,39 +86,159 @@ pub fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     world_attribute: Res<WorldAttribute>,
+    rules: Res<MatchRules>,
+    options: Res<LaunchOptions>,
 ) {
     // spawn_camera(&mut commands);
     spawn_light(&mut commands);
     spawn_ground(&mut commands, &mut meshes, &mut materials, &world_attribute);
-    spawn_player(&mut commands, &mut meshes, &mut materials);
+    if !options.headless {
+        spawn_player(&mut commands, &mut meshes, &mut materials, &rules);
+    }
     spawn_wall(&mut commands, &mut meshes, &mut materials, &world_attribute);
     spawn_boxes(&mut commands, &mut meshes, &mut materials);
+    spawn_spawn_points(&mut commands);
+    spawn_safe_zone_boundary(&mut commands, &mut meshes, &mut materials, &world_attribute);
+    spawn_capture_zone(&mut commands, &mut meshes, &mut materials);
 }
 
 fn main() {
-    let all_systems = (
+    let options = LaunchOptions::from_args(std::env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(2);
+    });
+    build_app(options).run();
+}
+
+/// Builds the game, headless or with a window depending on the launch options
+fn build_app(options: LaunchOptions) -> App {
+    let ui_systems = (
         camera_system,
         toggle_camera_mode_system,
         update_player_info_system,
         toggle_cursor,
         update_inventory_ui_system,
         update_durability_text_system,
-        threw_item_system
+        update_item_progress_ui_system,
+        update_ammo_text_system,
+        update_vitals_ui_system,
+        update_match_ui_system,
+        update_zone_ui_system,
+        update_capture_ui_system,
+        (toggle_nav_debug_system, draw_nav_debug_system).chain(),
+        (
+            kill_feed_system,
+            notification_system,
+            damage_vignette_system,
+            spawn_damage_indicator_system,
+            update_damage_indicator_system,
+            hit_marker_system,
+            spawn_damage_number_system,
+            update_damage_number_system,
+            spawn_nameplate_system,
+            update_nameplate_health_system,
+            world_anchor_system,
+            scoreboard_system,
+        ).chain().after(apply_damage_system).after(camera_system),
+    );
+
+    let combat_systems = (
+        (
+            projectile_collision_system,
+            projectile_impact_system,
+            projectile_timeout_system,
+        ).chain(),
+        thrower_clearance_system,
+        melee_swing_system,
+        fuse_system,
+        area_effect_system,
+        status_effect_system.after(area_effect_system),
+        apply_damage_system
+            .after(projectile_impact_system)
+            .after(melee_swing_system)
+            .after(fuse_system),
+        stagger_system,
+        armor_regen_system,
+        (start_respawn_system, respawn_system).chain().after(apply_damage_system),
+        spawn_protection_system.before(apply_damage_system),
     );
 
     let game_systems = (
-            update_jump_state_system,
-            player_movement_system,
-            melee_system,
-            check_weapon_durability_system,
+            (player_input_system, bot_think_system, bot_act_system, player_aim_system).chain(),
+            switch_weapon_system,
+            weapon_switch_delay_system,
+            (
+                block_system,
+                melee_system,
+                throw_weapon_system,
+                weapon_cooldown_system,
+                fire_weapon_system,
+                reload_input_system,
+                reload_system,
+                check_weapon_durability_system,
+            ).chain(),
             check_item_intersections,
+            pickup_radius_system,
+            pickup_delay_system,
+            drop_item_system,
+            apply_passive_modifiers_system,
             change_selected_item_system,
             use_item_system,
+            item_channel_system,
+            item_cooldown_system,
             speed_boost_system
         ).chain();
 
-    App::new()
-        .add_plugins((
+    // Movement is simulated in fixed ticks so a client's prediction plays out exactly like the server's simulation,
+    // Rapier steps in FixedPostUpdate right after them
+    let movement_systems =
+        (reconcile_system, record_input_system, update_jump_state_system, player_movement_system).chain();
+
+    let match_systems = (
+        main_menu_system.run_if(in_state(GameState::MainMenu)),
+        lobby_system.run_if(in_state(GameState::Lobby)),
+        warmup_system.run_if(in_state(GameState::Warmup)),
+        (record_damage_system, score_system, match_end_system)
+            .chain()
+            .after(apply_damage_system)
+            .run_if(in_state(GameState::InProgress)),
+        (safe_zone_system, zone_damage_system)
+            .chain()
+            .before(apply_damage_system)
+            .run_if(in_state(GameState::InProgress)),
+        zone_boundary_system,
+        trigger_volume_system,
+        (capture_zone_system, rotate_capture_zone_system)
+            .chain()
+            .after(trigger_volume_system)
+            .run_if(in_state(GameState::InProgress)),
+        capture_zone_visual_system,
+        post_match_system.run_if(in_state(GameState::PostMatch)),
+        reload_match_rules_system.run_if(in_state(GameState::Lobby).or(in_state(GameState::PostMatch))),
+        (assign_team_system, team_color_system).chain(),
+        update_nav_grid_system.before(bot_think_system),
+    );
+
+    let mut app = App::new();
+    if options.headless {
+        // Only what the simulation needs, nothing is drawn and nobody plays on this machine
+        app.add_plugins((
+            MinimalPlugins,
+            // Match results are logged, so headless output can be filtered like the rest
+            LogPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+            AssetPlugin::default(),
+            // Rapier's async colliders look for meshes and scenes
+            ScenePlugin,
+            StatesPlugin,
+            InputPlugin,
+            RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule(),
+        ))
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>();
+    } else {
+        app.add_plugins((
             DefaultPlugins.set(WindowPlugin {
                 primary_window: Some(Window {
                     mode: WindowMode::Windowed,
@@ -80,9 +249,21 @@ fn main() {
             }),
             // DefaultPlugins,
             FrameTimeDiagnosticsPlugin,
-            RapierPhysicsPlugin::<NoUserData>::default(),
+            RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule(),
             RapierDebugRenderPlugin::default(),
         ))
+        .add_systems(Startup, (maximize_window, hide_cursor))
+        .add_systems(Startup, (setup_debug_ui, setup_game_ui).chain())
+        .add_systems(Update, ui_systems);
+    }
+
+    app
+        .insert_resource(Time::<Fixed>::from_hz(MOVEMENT_TICK_RATE))
+        // One physics step per tick, so the same inputs always give the same movement
+        .insert_resource(TimestepMode::Fixed {
+            dt: (1.0 / MOVEMENT_TICK_RATE) as f32,
+            substeps: 1,
+        })
         .insert_resource(DebugPrintTimer(Timer::new(
             Duration::from_millis(500),
             TimerMode::Repeating,
@@ -90,10 +271,100 @@ fn main() {
         .insert_resource(ClearColor(Color::srgb_u8(127, 127, 127)))
         .insert_resource(WorldAttribute::default())
         .init_resource::<CameraState>()
-        .init_resource::<GameState>()
-        .add_systems(Startup, (maximize_window, setup, hide_cursor, spawn_items))
-        .add_systems(Startup, (setup_debug_ui, setup_game_ui).chain())
-        .add_systems(Update, all_systems)
-        .add_systems(Update, game_systems)
-        .run();
+        .init_state::<GameState>()
+        .insert_resource(MatchRules::load_or_default(MATCH_RULES_PATH))
+        .insert_resource(options)
+        .init_resource::<MatchSettings>()
+        .init_resource::<MatchClock>()
+        .init_resource::<ItemAssets>()
+        .init_resource::<GameRng>()
+        .init_resource::<DamageRules>()
+        .init_resource::<TeamAssets>()
+        .init_resource::<SafeZone>()
+        .init_resource::<NavGrid>()
+        .init_resource::<NavDebug>()
+        .add_event::<DamageEvent>()
+        .add_event::<ProjectileImpactEvent>()
+        .add_event::<DamageTakenEvent>()
+        .add_event::<DeathEvent>()
+        .add_event::<AttackEvent>()
+        .add_event::<NotificationEvent>()
+        .add_event::<AuthoritativeStateEvent>()
+        .add_systems(Startup, (setup, apply_match_rules_system))
+        .add_systems(OnEnter(GameState::Lobby), fill_bot_slots_system)
+        .add_systems(
+            OnEnter(GameState::Warmup),
+            (
+                start_warmup_system,
+                fill_bot_slots_system,
+                // Bots filled in just now need a team before they are balanced and spawned
+                assign_team_system,
+                balance_teams_system,
+                reset_arena_system,
+                reset_safe_zone_system,
+                reset_capture_zone_system,
+                spawn_items,
+                tune_items_system,
+            ).chain(),
+        )
+        .add_systems(
+            OnEnter(GameState::InProgress),
+            (
+                start_match_system,
+                assign_team_system,
+                balance_teams_system,
+                reset_arena_system,
+                reset_safe_zone_system,
+                reset_capture_zone_system,
+                spawn_items,
+                tune_items_system,
+            ).chain(),
+        )
+        .add_systems(
+            OnEnter(GameState::PostMatch),
+            (start_post_match_system, reset_safe_zone_system, reset_capture_zone_system),
+        )
+        .add_systems(FixedUpdate, movement_systems.run_if(gameplay_running))
+        .add_systems(
+            FixedPostUpdate,
+            record_displacement_system.after(PhysicsSet::Writeback).run_if(gameplay_running),
+        )
+        .add_systems(Update, combat_systems.run_if(gameplay_running))
+        .add_systems(Update, game_systems.run_if(gameplay_running))
+        .add_systems(Update, match_systems);
+    app
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::bot::Bot;
+    use bevy::time::TimeUpdateStrategy;
+
+    #[test]
+    fn headless_match_with_bots_is_played_to_the_end() {
+        let mut app = build_app(LaunchOptions {
+            headless: true,
+            bots: Some(3),
+            matches: None,
+        });
+        // A short match, simulated a tenth of a second per update
+        app.insert_resource(MatchRules {
+            warmup_time: 1.0,
+            time_limit: 5.0,
+            ..default()
+        })
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+
+        for _ in 0..1000 {
+            app.update();
+            if app.world().resource::<MatchClock>().played > 0 {
+                break;
+            }
+        }
+
+        assert_eq!(app.world().resource::<MatchClock>().played, 1);
+        let bots = app.world_mut().query::<&Bot>().iter(app.world()).count();
+        assert_eq!(bots, 3);
+    }
 }
@@ -3,17 +3,124 @@ use bevy::color::palettes::tailwind::LIME_200;
 use bevy::prelude::*;
 
 /**
-GameState store information about game state.
+Where the game is in the flow of a match.
+Gameplay only runs during warmup and the match itself.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, States)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    /// Waiting for players before the warmup starts
+    Lobby,
+    /// Players can fight, but nothing counts towards the score
+    Warmup,
+    InProgress,
+    /// The match is over and its summary is shown until the next one starts
+    PostMatch,
+}
+
+impl GameState {
+    /// Whether players can move and fight in this state
+    pub fn is_playing(&self) -> bool {
+        matches!(self, GameState::Warmup | GameState::InProgress)
+    }
+}
+
+/// Rules a match is played by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameMode {
+    /// Everyone fights everyone
+    #[default]
+    Deathmatch,
+    /// Two teams fight each other, kills count towards the team
+    TeamDeathmatch,
+    /// Everyone has one life while the safe zone closes in, the last player alive wins
+    LastStanding,
+    /// Players score by holding the hill alone, the first to hold it long enough wins
+    KingOfTheHill,
+}
+
+impl GameMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Deathmatch => "Deathmatch",
+            GameMode::TeamDeathmatch => "Team Deathmatch",
+            GameMode::LastStanding => "Last Player Standing",
+            GameMode::KingOfTheHill => "King of the Hill",
+        }
+    }
+
+    /// Whether dead players spawn again during the match
+    pub fn has_respawns(&self) -> bool {
+        !matches!(self, GameMode::LastStanding)
+    }
+
+    pub fn has_teams(&self) -> bool {
+        matches!(self, GameMode::TeamDeathmatch)
+    }
+}
+
+/**
+Settings of a match, a match ends on whichever limit is reached first
+ */
+#[derive(Debug, Resource)]
+pub struct MatchSettings {
+    pub mode: GameMode,
+    /// Seconds the match lasts
+    pub time_limit: f32,
+    /// Kills a player, or a team in team modes, needs to win the match right away
+    pub frag_limit: u32,
+    /// Seconds a player needs to hold the hill to win in king of the hill
+    pub hold_limit: u32,
+    pub warmup_time: f32,
+    /// Seconds the summary is shown before the next match starts
+    pub post_match_time: f32,
+    /// Players needed in the lobby before the warmup can start
+    pub min_players: usize,
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        MatchSettings {
+            mode: GameMode::default(),
+            time_limit: 300.,
+            frag_limit: 20,
+            hold_limit: 100,
+            warmup_time: 10.,
+            post_match_time: 10.,
+            min_players: 1,
+        }
+    }
+}
+
+/**
+Clock of the current warmup, match or post-match summary, and the result of the last match
+ */
+#[derive(Debug, Default, Resource)]
+pub struct MatchClock {
+    pub timer: Timer,
+    /// Name of the player or team that won the last match, `None` for a draw
+    pub winner: Option<String>,
+    /// Matches finished since the game started
+    pub played: u32,
+}
+
+/**
+Rules deciding who can be damaged by whom
  */
 #[derive(Debug, Resource)]
-pub struct GameState {
-    pub position: Vec3,
+pub struct DamageRules {
+    /// Whether players can hurt themselves, e.g. with their own bouncing rock
+    pub self_damage: bool,
+    /// Whether players can hurt their teammates
+    pub friendly_fire: bool,
 }
 
-impl Default for GameState {
+impl Default for DamageRules {
     fn default() -> Self {
-        GameState {
-            position: Vec3::ZERO,
+        DamageRules {
+            self_damage: false,
+            friendly_fire: false,
         }
     }
 }
@@ -0,0 +1,85 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+
+use crate::components::player::{Item, ItemCategory};
+
+/**
+ItemAssets store the shared mesh and materials used by item pickups,
+so items can be spawned into the world again after they are dropped.
+ */
+#[derive(Debug, Resource)]
+pub struct ItemAssets {
+    pub mesh: Handle<Mesh>,
+    pub buff_material: Handle<StandardMaterial>,
+    pub heal_material: Handle<StandardMaterial>,
+    pub throw_material: Handle<StandardMaterial>,
+    pub weapon_material: Handle<StandardMaterial>,
+    pub passive_material: Handle<StandardMaterial>,
+    pub ammo_material: Handle<StandardMaterial>,
+    pub armor_material: Handle<StandardMaterial>,
+    /// Small mesh used by projectiles fired from ranged weapons
+    pub bullet_mesh: Handle<Mesh>,
+    pub bullet_material: Handle<StandardMaterial>,
+    pub explosive_material: Handle<StandardMaterial>,
+    /// See-through materials of the volumes left by smoke and slow throwables
+    pub smoke_material: Handle<StandardMaterial>,
+    pub slow_field_material: Handle<StandardMaterial>,
+    /// Bubble around players that just spawned and can't be hurt yet
+    pub spawn_protection_material: Handle<StandardMaterial>,
+}
+
+impl ItemAssets {
+    /// Returns the material used to display the item as a pickup
+    pub fn material_for(&self, item: &Item) -> Handle<StandardMaterial> {
+        match item.effect.category() {
+            ItemCategory::Buff => self.buff_material.clone(),
+            ItemCategory::Heal => self.heal_material.clone(),
+            ItemCategory::Throw => self.throw_material.clone(),
+            ItemCategory::Weapon => self.weapon_material.clone(),
+            ItemCategory::Passive => self.passive_material.clone(),
+            ItemCategory::Ammo => self.ammo_material.clone(),
+            ItemCategory::Armor => self.armor_material.clone(),
+        }
+    }
+}
+
+impl FromWorld for ItemAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let mesh = meshes.add(Sphere::new(0.5));
+        let bullet_mesh = meshes.add(Sphere::new(0.1));
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let smoke_material = materials.add(StandardMaterial {
+            base_color: Color::from(GRAY_300.with_alpha(0.9)),
+            alpha_mode: AlphaMode::Blend,
+            // Still block the view from inside the smoke
+            cull_mode: None,
+            double_sided: true,
+            ..default()
+        });
+        let mut material = |color: Srgba| {
+            materials.add(StandardMaterial {
+                base_color: Color::from(color),
+                alpha_mode: if color.alpha < 1.0 { AlphaMode::Blend } else { AlphaMode::Opaque },
+                ..default()
+            })
+        };
+
+        Self {
+            mesh,
+            buff_material: material(YELLOW_300),
+            heal_material: material(ROSE_400),
+            throw_material: material(GREEN_400),
+            weapon_material: material(CYAN_400),
+            passive_material: material(VIOLET_400),
+            ammo_material: material(AMBER_600),
+            armor_material: material(SLATE_400),
+            bullet_mesh,
+            bullet_material: material(ZINC_800),
+            explosive_material: material(RED_600),
+            smoke_material,
+            slow_field_material: material(SKY_400.with_alpha(0.35)),
+            spawn_protection_material: material(AMBER_300.with_alpha(0.3)),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+
+/**
+How the game was started, read from the command line.
+`--headless` runs without a window or local player, `--bots N` sets how many bots join
+instead of filling the slots the match rules ask for,
+and `--matches N` quits after that many matches, so bot matches can be simulated.
+ */
+#[derive(Debug, Clone, Default, Resource)]
+pub struct LaunchOptions {
+    pub headless: bool,
+    pub bots: Option<usize>,
+    /// Matches played before the game quits, `None` keeps playing
+    pub matches: Option<u32>,
+}
+
+impl LaunchOptions {
+    /// Parses the command line arguments, without the program name
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = LaunchOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => options.headless = true,
+                "--bots" => options.bots = Some(parse_number(&arg, args.next())?),
+                "--matches" => options.matches = Some(parse_number(&arg, args.next())?),
+                _ => return Err(format!("unknown argument `{}`", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+// HELPER FUNCTION
+
+/// Helper function to parse the number following a flag
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("`{}` needs a number", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid number `{}` for `{}`", value, flag))
+}
@@ -1,5 +1,12 @@
 pub mod camera;
 pub mod game;
+pub mod item;
+pub mod launch;
+pub mod navigation;
+pub mod rng;
+pub mod rules;
+pub mod team;
+pub mod zone;
 
 use bevy::prelude::*;
 
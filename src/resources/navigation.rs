@@ -0,0 +1,422 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use bevy::prelude::*;
+
+/// Width of a navigation cell
+pub const NAV_CELL_SIZE: f32 = 1.0;
+/// Half the width of a player, walls are grown by this much so paths keep players clear of them
+pub const NAV_AGENT_RADIUS: f32 = 0.5;
+/// Highest step up a player can jump onto
+pub const NAV_MAX_CLIMB: f32 = 2.5;
+/// Extra cost of climbing onto something, so paths only jump when it saves a real detour
+const CLIMB_COST: f32 = 4.0;
+/// How many cells away from a blocked start or end the nearest walkable cell is searched for
+const NEAREST_SEARCH: i32 = 3;
+
+/// A cell of the [`NavGrid`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NavCell {
+    Blocked,
+    /// Players can stand here, on a surface at this height
+    Walkable(f32),
+}
+
+/// A box of the static world the grid is built from
+#[derive(Debug, Clone, Copy)]
+pub struct NavObstacle {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    /// Whether players can stand on top of it, like the ground and structures, or only walk around it, like walls
+    pub walkable_top: bool,
+}
+
+/**
+Walkable grid over the arena, built from the ground, structures and walls.
+Each cell knows the height of the surface players stand on there, so paths can climb onto structures.
+*/
+#[derive(Debug, Default, Resource)]
+pub struct NavGrid {
+    /// World position of the corner of the first cell
+    pub origin: Vec2,
+    pub columns: usize,
+    pub rows: usize,
+    pub cells: Vec<NavCell>,
+}
+
+impl NavGrid {
+    /// Builds the grid over the area from `min` to `max` on the ground
+    pub fn build(min: Vec2, max: Vec2, obstacles: &[NavObstacle]) -> Self {
+        let columns = ((max.x - min.x) / NAV_CELL_SIZE).ceil().max(0.0) as usize;
+        let rows = ((max.y - min.y) / NAV_CELL_SIZE).ceil().max(0.0) as usize;
+        let mut grid = NavGrid {
+            origin: min,
+            columns,
+            rows,
+            cells: vec![NavCell::Blocked; columns * rows],
+        };
+        for index in 0..grid.cells.len() {
+            let center = grid.cell_center(index);
+            grid.cells[index] = classify_cell(center, obstacles);
+        }
+        grid
+    }
+
+    /// World position of the middle of a cell, at the height of its surface
+    pub fn cell_position(&self, index: usize) -> Vec3 {
+        let center = self.cell_center(index);
+        let height = match self.cells[index] {
+            NavCell::Walkable(height) => height,
+            NavCell::Blocked => 0.0,
+        };
+        Vec3::new(center.x, height, center.y)
+    }
+
+    /// The cell a world position lies in, if it is on the grid
+    pub fn cell_at(&self, position: Vec3) -> Option<usize> {
+        let local = (position.xz() - self.origin) / NAV_CELL_SIZE;
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+        let (column, row) = (local.x as usize, local.y as usize);
+        (column < self.columns && row < self.rows).then(|| row * self.columns + column)
+    }
+
+    pub fn is_walkable(&self, index: usize) -> bool {
+        matches!(self.cells.get(index), Some(NavCell::Walkable(_)))
+    }
+
+    /**
+    Finds a path from `from` to `to` with A*, smoothed so it only turns where it has to.
+    The path holds the points to walk through after `from`, at the height of the surface under them,
+    and ends at `to`, or next to it when `to` is blocked. `None` when there is no way there.
+    */
+    pub fn find_path(&self, from: Vec3, to: Vec3) -> Option<Vec<Vec3>> {
+        let start = self.nearest_walkable(from)?;
+        let goal = self.nearest_walkable(to)?;
+        let cells = self.search(start, goal)?;
+
+        let mut path = self.smooth(&cells);
+        // End right at the target when players can stand there, otherwise next to it
+        if let Some(last) = path.last_mut().filter(|_| self.cell_at(to) == Some(goal)) {
+            *last = Vec3::new(to.x, last.y, to.z);
+        }
+        Some(path)
+    }
+
+    fn cell_center(&self, index: usize) -> Vec2 {
+        let (column, row) = (index % self.columns, index / self.columns);
+        self.origin + (Vec2::new(column as f32, row as f32) + 0.5) * NAV_CELL_SIZE
+    }
+
+    /// The cell at a position, or the closest walkable cell around it when it is blocked
+    fn nearest_walkable(&self, position: Vec3) -> Option<usize> {
+        let cell = self.cell_at(position)?;
+        if self.is_walkable(cell) {
+            return Some(cell);
+        }
+        let (column, row) = ((cell % self.columns) as i32, (cell / self.columns) as i32);
+        let mut best: Option<(usize, i32)> = None;
+        for dz in -NEAREST_SEARCH..=NEAREST_SEARCH {
+            for dx in -NEAREST_SEARCH..=NEAREST_SEARCH {
+                let Some(other) = self.offset(column + dx, row + dz) else {
+                    continue;
+                };
+                let distance = dx * dx + dz * dz;
+                if self.is_walkable(other) && best.is_none_or(|(_, closest)| distance < closest) {
+                    best = Some((other, distance));
+                }
+            }
+        }
+        best.map(|(other, _)| other)
+    }
+
+    fn offset(&self, column: i32, row: i32) -> Option<usize> {
+        let inside = column >= 0 && row >= 0 && (column as usize) < self.columns && (row as usize) < self.rows;
+        inside.then(|| row as usize * self.columns + column as usize)
+    }
+
+    /// A* over the cells, moving in eight directions without cutting past blocked corners
+    fn search(&self, start: usize, goal: usize) -> Option<Vec<usize>> {
+        let mut came_from = vec![usize::MAX; self.cells.len()];
+        let mut cost = vec![f32::INFINITY; self.cells.len()];
+        let mut closed = vec![false; self.cells.len()];
+        let mut open = BinaryHeap::new();
+        cost[start] = 0.0;
+        open.push(OpenCell { estimate: self.heuristic(start, goal), index: start });
+
+        while let Some(OpenCell { index, .. }) = open.pop() {
+            if index == goal {
+                let mut cells = vec![goal];
+                let mut current = goal;
+                while current != start {
+                    current = came_from[current];
+                    cells.push(current);
+                }
+                cells.reverse();
+                return Some(cells);
+            }
+            // A cell can be on the open list more than once, only the cheapest visit counts
+            if std::mem::replace(&mut closed[index], true) {
+                continue;
+            }
+            let (column, row) = ((index % self.columns) as i32, (index / self.columns) as i32);
+            for (dx, dz) in NEIGHBORS {
+                let Some(next) = self.offset(column + dx, row + dz) else {
+                    continue;
+                };
+                let Some(step) = self.step_cost(index, next) else {
+                    continue;
+                };
+                // Diagonal moves need both cells beside them to be open, so paths don't clip corners
+                if dx != 0 && dz != 0 {
+                    let beside = [self.offset(column + dx, row), self.offset(column, row + dz)];
+                    if !beside.iter().all(|cell| cell.is_some_and(|cell| self.step_cost(index, cell).is_some())) {
+                        continue;
+                    }
+                }
+                let next_cost = cost[index] + step;
+                if next_cost < cost[next] {
+                    cost[next] = next_cost;
+                    came_from[next] = index;
+                    open.push(OpenCell { estimate: next_cost + self.heuristic(next, goal), index: next });
+                }
+            }
+        }
+        None
+    }
+
+    /// Cost of stepping between two neighboring cells, `None` when the step is impossible
+    fn step_cost(&self, from: usize, to: usize) -> Option<f32> {
+        let (NavCell::Walkable(from_height), NavCell::Walkable(to_height)) = (self.cells[from], self.cells[to]) else {
+            return None;
+        };
+        let climb = to_height - from_height;
+        if climb > NAV_MAX_CLIMB {
+            return None;
+        }
+        let distance = self.cell_center(from).distance(self.cell_center(to));
+        Some(if climb > f32::EPSILON { distance + CLIMB_COST } else { distance })
+    }
+
+    fn heuristic(&self, from: usize, to: usize) -> f32 {
+        self.cell_center(from).distance(self.cell_center(to))
+    }
+
+    /// Drops every point that can be skipped by walking straight on the same surface
+    fn smooth(&self, cells: &[usize]) -> Vec<Vec3> {
+        let mut path = Vec::new();
+        let mut current = 0;
+        while current + 1 < cells.len() {
+            let mut next = current + 1;
+            while next + 1 < cells.len() && self.is_straight_walkable(cells[current], cells[next + 1]) {
+                next += 1;
+            }
+            path.push(self.cell_position(cells[next]));
+            current = next;
+        }
+        if path.is_empty() {
+            path.extend(cells.first().map(|cell| self.cell_position(*cell)));
+        }
+        path
+    }
+
+    /// Whether the straight line between two cells only crosses walkable cells at the same height
+    fn is_straight_walkable(&self, from: usize, to: usize) -> bool {
+        let NavCell::Walkable(height) = self.cells[from] else {
+            return false;
+        };
+        let (start, end) = (self.cell_center(from), self.cell_center(to));
+        let samples = (start.distance(end) / (NAV_CELL_SIZE * 0.25)).ceil() as usize;
+        (0..=samples).all(|sample| {
+            let point = start.lerp(end, sample as f32 / samples.max(1) as f32);
+            // Check a player's width around the line, not just its middle
+            let side = (end - start).perp().normalize_or_zero() * NAV_AGENT_RADIUS;
+            [point, point + side, point - side].into_iter().all(|point| {
+                self.cell_at(Vec3::new(point.x, 0.0, point.y))
+                    .is_some_and(|cell| matches!(self.cells[cell], NavCell::Walkable(other) if (other - height).abs() < 0.01))
+            })
+        })
+    }
+}
+
+/// Cell on the open list of the A* search, ordered so the heap pops the lowest estimate first
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OpenCell {
+    estimate: f32,
+    index: usize,
+}
+
+impl Eq for OpenCell {}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Whether the navigation grid and the paths of bots are drawn over the arena, toggled with N
+#[derive(Debug, Default, Resource)]
+pub struct NavDebug {
+    pub visible: bool,
+}
+
+const NEIGHBORS: [(i32, i32); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+// HELPER FUNCTION
+
+/// Helper function to find what a cell is, standing on the highest surface above it unless a wall is in the way
+fn classify_cell(center: Vec2, obstacles: &[NavObstacle]) -> NavCell {
+    let mut cell = NavCell::Blocked;
+    for obstacle in obstacles {
+        match (cell, cell_from_obstacle(center, obstacle)) {
+            (_, Some(NavCell::Blocked)) => return NavCell::Blocked,
+            (NavCell::Walkable(old), Some(NavCell::Walkable(new))) => cell = NavCell::Walkable(old.max(new)),
+            (NavCell::Blocked, Some(new)) => cell = new,
+            (_, None) => {}
+        }
+    }
+    cell
+}
+
+/**
+Helper function to find what a single box makes of a cell, `None` when the box doesn't touch it.
+Walls block everything within a player's width of them, surfaces only count under the cell's middle.
+*/
+fn cell_from_obstacle(center: Vec2, obstacle: &NavObstacle) -> Option<NavCell> {
+    let offset = (center - obstacle.center.xz()).abs();
+    let half = obstacle.half_extents.xz();
+    if obstacle.walkable_top {
+        let top = obstacle.center.y + obstacle.half_extents.y;
+        (offset.x <= half.x && offset.y <= half.y).then_some(NavCell::Walkable(top))
+    } else {
+        let grown = half + NAV_AGENT_RADIUS;
+        (offset.x < grown.x && offset.y < grown.y).then_some(NavCell::Blocked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat ground from the origin to `size` in both directions, with its surface at height 0
+    fn ground(size: f32) -> NavObstacle {
+        NavObstacle {
+            center: Vec3::new(size / 2.0, -0.5, size / 2.0),
+            half_extents: Vec3::new(size / 2.0, 0.5, size / 2.0),
+            walkable_top: true,
+        }
+    }
+
+    /// A wall just thin enough to block the single cell it stands in
+    fn post(column: usize, row: usize) -> NavObstacle {
+        NavObstacle {
+            center: Vec3::new(column as f32 + 0.5, 1.0, row as f32 + 0.5),
+            half_extents: Vec3::new(0.25, 1.0, 0.25),
+            walkable_top: false,
+        }
+    }
+
+    fn grid(size: f32, obstacles: &[NavObstacle]) -> NavGrid {
+        let mut all = vec![ground(size)];
+        all.extend_from_slice(obstacles);
+        NavGrid::build(Vec2::ZERO, Vec2::splat(size), &all)
+    }
+
+    /// Checks that walking the path from `from` never crosses a blocked cell
+    fn assert_walkable(grid: &NavGrid, from: Vec3, path: &[Vec3]) {
+        let mut previous = from;
+        for point in path {
+            for sample in 0..=20 {
+                let position = previous.lerp(*point, sample as f32 / 20.0);
+                let cell = grid.cell_at(position).expect("path leaves the grid");
+                assert!(grid.is_walkable(cell), "path crosses a blocked cell at {}", position);
+            }
+            previous = *point;
+        }
+    }
+
+    #[test]
+    fn open_ground_is_walked_in_a_straight_line() {
+        let grid = grid(10.0, &[]);
+        let path = grid.find_path(Vec3::new(1.5, 1.0, 1.5), Vec3::new(8.5, 1.0, 8.5)).unwrap();
+
+        assert_eq!(path, vec![Vec3::new(8.5, 0.0, 8.5)]);
+    }
+
+    #[test]
+    fn walls_are_walked_around() {
+        // A wall from z = 0 to 7 between the start and the goal
+        let wall = NavObstacle {
+            center: Vec3::new(5.0, 1.0, 3.5),
+            half_extents: Vec3::new(0.5, 1.0, 3.5),
+            walkable_top: false,
+        };
+        let grid = grid(10.0, &[wall]);
+        let from = Vec3::new(1.5, 1.0, 1.5);
+        let path = grid.find_path(from, Vec3::new(8.5, 1.0, 1.5)).unwrap();
+
+        assert!(path.len() > 1);
+        assert!(path.iter().any(|point| point.z > 7.0));
+        assert_walkable(&grid, from, &path);
+        assert_eq!(path.last(), Some(&Vec3::new(8.5, 0.0, 1.5)));
+    }
+
+    #[test]
+    fn diagonal_moves_do_not_cut_corners() {
+        // A post next to the diagonal forces the path through the open side
+        let one_side = grid(3.0, &[post(1, 0)]);
+        assert_eq!(one_side.search(0, 4), Some(vec![0, 3, 4]));
+
+        // With both sides blocked the corner can't be squeezed through at all
+        let both_sides = grid(3.0, &[post(1, 0), post(0, 1)]);
+        assert_eq!(both_sides.search(0, 4), None);
+        assert_eq!(both_sides.find_path(Vec3::new(0.5, 1.0, 0.5), Vec3::new(2.5, 1.0, 2.5)), None);
+    }
+
+    #[test]
+    fn blocked_goal_ends_next_to_it() {
+        let grid = grid(5.0, &[post(2, 2)]);
+        let goal = Vec3::new(2.5, 1.0, 2.5);
+        let path = grid.find_path(Vec3::new(0.5, 1.0, 0.5), goal).unwrap();
+
+        let last = *path.last().unwrap();
+        assert!(grid.is_walkable(grid.cell_at(last).unwrap()));
+        assert_ne!(last.xz(), goal.xz());
+        assert!(last.xz().distance(goal.xz()) < 2.0);
+    }
+
+    #[test]
+    fn blocked_start_leaves_from_the_nearest_walkable_cell() {
+        let grid = grid(5.0, &[post(0, 0)]);
+        let from = Vec3::new(0.5, 1.0, 0.5);
+        let path = grid.find_path(from, Vec3::new(4.5, 1.0, 4.5)).unwrap();
+
+        assert_eq!(path.last(), Some(&Vec3::new(4.5, 0.0, 4.5)));
+        assert_walkable(&grid, path[0], &path[1..]);
+    }
+
+    #[test]
+    fn only_low_structures_can_be_climbed() {
+        let structure = |height: f32| NavObstacle {
+            center: Vec3::new(5.0, height / 2.0, 5.0),
+            half_extents: Vec3::new(1.5, height / 2.0, 1.5),
+            walkable_top: true,
+        };
+        let (from, top) = (Vec3::new(0.5, 1.0, 0.5), Vec3::new(5.0, 5.0, 5.0));
+
+        let low = grid(10.0, &[structure(NAV_MAX_CLIMB - 0.5)]);
+        let path = low.find_path(from, top).unwrap();
+        assert_eq!(path.last(), Some(&Vec3::new(5.0, NAV_MAX_CLIMB - 0.5, 5.0)));
+
+        let high = grid(10.0, &[structure(NAV_MAX_CLIMB + 1.0)]);
+        assert_eq!(high.find_path(from, top), None);
+        // Jumping down is always possible
+        assert!(high.find_path(top, from).is_some());
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use bevy::prelude::*;
+
+/**
+GameRng is a small xorshift random number generator shared by gameplay
+systems, e.g. for weapon spread.
+ */
+#[derive(Debug, Resource)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on a zero state
+        Self { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    /// Returns a random number in `0.0..1.0`
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns a random number in `min..max`
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Rotates `direction` by a random angle of at most `max_angle` radians
+    pub fn direction_in_cone(&mut self, direction: Vec3, max_angle: f32) -> Vec3 {
+        if max_angle <= 0.0 {
+            return direction;
+        }
+        let (right, up) = direction.normalize().any_orthonormal_pair();
+        let around = self.range(0.0, std::f32::consts::TAU);
+        // sqrt keeps the shots evenly spread over the cone instead of bunching in the middle
+        let angle = max_angle * self.next_f32().sqrt();
+        let offset = (right * around.cos() + up * around.sin()) * angle.tan();
+        (direction.normalize() + offset).normalize()
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0x2545_F491_4F6C_DD1D);
+        Self::new(seed)
+    }
+}
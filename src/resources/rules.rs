@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+use bevy::prelude::*;
+
+use crate::components::bot::BotDifficulty;
+use crate::components::player::{Item, ItemEffect};
+use crate::resources::game::{DamageRules, GameMode, MatchSettings};
+
+/// Where the match rules are loaded from, relative to the working directory
+pub const MATCH_RULES_PATH: &str = "assets/config/match_rules.cfg";
+
+/// Tuning of a single item, values that aren't set keep what the item comes with
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemOverride {
+    pub damage: Option<f32>,
+    /// Only used by weapons
+    pub durability: Option<u16>,
+}
+
+/**
+Rules and tuning of a match, loaded from [`MATCH_RULES_PATH`] and reloadable between matches.
+The file has one `key = value` per line, `#` starts a comment and keys that are left out keep their default.
+ */
+#[derive(Debug, Clone, Resource)]
+pub struct MatchRules {
+    pub mode: GameMode,
+    /// Seconds a match lasts
+    pub time_limit: f32,
+    /// Kills a player, or a team in team modes, needs to win the match right away
+    pub score_limit: u32,
+    /// Seconds a player needs to hold the hill to win in king of the hill
+    pub hold_limit: u32,
+    pub warmup_time: f32,
+    pub post_match_time: f32,
+    pub min_players: usize,
+    pub friendly_fire: bool,
+    pub self_damage: bool,
+    /// Players a match is filled up to with bots, 0 for no bots
+    pub bot_slots: usize,
+    pub bot_difficulty: BotDifficulty,
+    /// Seconds a dead player waits before spawning again
+    pub respawn_delay: f32,
+    /// Seconds a newly spawned player can't be damaged
+    pub spawn_protection: f32,
+    pub starting_health: f32,
+    pub move_speed: f32,
+    /// Upwards impulse of a jump
+    pub jump_impulse: f32,
+    pub gravity_scale: f32,
+    /// Speed weapons and rocks are thrown at
+    pub throw_speed: f32,
+    /// Speed throwables with a fuse are lobbed at, slower so they roll to a stop before going off
+    pub lob_speed: f32,
+    /// Seconds a thrown object stays in the world before it is removed
+    pub projectile_lifetime: f32,
+    pub speed_boost_amount: f32,
+    pub speed_boost_duration: f32,
+    /// Tuning of single items by name, set with `item.<name>.<field> = value`
+    pub items: HashMap<String, ItemOverride>,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        MatchRules {
+            mode: GameMode::default(),
+            time_limit: 300.,
+            score_limit: 20,
+            hold_limit: 100,
+            warmup_time: 10.,
+            post_match_time: 10.,
+            min_players: 1,
+            friendly_fire: false,
+            self_damage: false,
+            bot_slots: 4,
+            bot_difficulty: BotDifficulty::default(),
+            respawn_delay: 3.,
+            spawn_protection: 3.,
+            starting_health: 100.,
+            move_speed: 15.,
+            jump_impulse: 10.,
+            gravity_scale: 2.,
+            throw_speed: 30.,
+            lob_speed: 20.,
+            projectile_lifetime: 5.,
+            speed_boost_amount: 10.,
+            speed_boost_duration: 10.,
+            items: HashMap::new(),
+        }
+    }
+}
+
+impl MatchRules {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("can't read {}: {}", path, error))?;
+        Self::parse(&text)
+    }
+
+    /// Loads the rules, falling back to the defaults when the file is missing or broken
+    pub fn load_or_default(path: &str) -> Self {
+        Self::load(path).unwrap_or_else(|error| {
+            warn!("Using the default match rules, {}", error);
+            Self::default()
+        })
+    }
+
+    /// Parses the contents of a rules file on top of the defaults
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rules = MatchRules::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", index + 1));
+            };
+            rules
+                .set(key.trim(), value.trim())
+                .map_err(|error| format!("line {}: {}", index + 1, error))?;
+        }
+        Ok(rules)
+    }
+
+    /// Copies the rules of the match flow into the settings and damage rules the match is played by
+    pub fn apply_to(&self, settings: &mut MatchSettings, damage_rules: &mut DamageRules) {
+        settings.mode = self.mode;
+        settings.time_limit = self.time_limit;
+        settings.frag_limit = self.score_limit;
+        settings.hold_limit = self.hold_limit;
+        settings.warmup_time = self.warmup_time;
+        settings.post_match_time = self.post_match_time;
+        settings.min_players = self.min_players;
+        damage_rules.friendly_fire = self.friendly_fire;
+        damage_rules.self_damage = self.self_damage;
+    }
+
+    /// Applies the item tuning to an item placed in the arena
+    pub fn tune_item(&self, item: &mut Item) {
+        match &mut item.effect {
+            ItemEffect::IncreaseSpeed { amount, duration } => {
+                *amount = self.speed_boost_amount;
+                *duration = self.speed_boost_duration;
+            }
+            ItemEffect::Throw { speed, fuse: None, .. } => *speed = self.throw_speed,
+            ItemEffect::Throw { speed, fuse: Some(_), .. } => *speed = self.lob_speed,
+            _ => {}
+        }
+
+        let Some(tuning) = self.items.get(&item.name) else {
+            return;
+        };
+        match &mut item.effect {
+            ItemEffect::WeaponItem(weapon) => {
+                weapon.damage = tuning.damage.unwrap_or(weapon.damage);
+                weapon.durability = tuning.durability.unwrap_or(weapon.durability);
+            }
+            ItemEffect::Throw { damage, .. } => *damage = tuning.damage.unwrap_or(*damage),
+            _ => {}
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "mode" => self.mode = parse_mode(value)?,
+            "time_limit" => self.time_limit = parse_amount(key, value)?,
+            "score_limit" => self.score_limit = parse_value(key, value)?,
+            "hold_limit" => self.hold_limit = parse_value(key, value)?,
+            "warmup_time" => self.warmup_time = parse_amount(key, value)?,
+            "post_match_time" => self.post_match_time = parse_amount(key, value)?,
+            "min_players" => self.min_players = parse_value(key, value)?,
+            "friendly_fire" => self.friendly_fire = parse_value(key, value)?,
+            "self_damage" => self.self_damage = parse_value(key, value)?,
+            "bot_slots" => self.bot_slots = parse_value(key, value)?,
+            "bot_difficulty" => self.bot_difficulty = parse_difficulty(value)?,
+            "respawn_delay" => self.respawn_delay = parse_amount(key, value)?,
+            "spawn_protection" => self.spawn_protection = parse_amount(key, value)?,
+            "starting_health" => self.starting_health = parse_amount(key, value)?,
+            "move_speed" => self.move_speed = parse_amount(key, value)?,
+            "jump_impulse" => self.jump_impulse = parse_amount(key, value)?,
+            "gravity_scale" => self.gravity_scale = parse_amount(key, value)?,
+            "throw_speed" => self.throw_speed = parse_amount(key, value)?,
+            "lob_speed" => self.lob_speed = parse_amount(key, value)?,
+            "projectile_lifetime" => self.projectile_lifetime = parse_amount(key, value)?,
+            "speed_boost_amount" => self.speed_boost_amount = parse_amount(key, value)?,
+            "speed_boost_duration" => self.speed_boost_duration = parse_amount(key, value)?,
+            _ => {
+                // Item names can have spaces and dots, the field is whatever follows the last dot
+                let Some((name, field)) = key.strip_prefix("item.").and_then(|rest| rest.rsplit_once('.')) else {
+                    return Err(format!("unknown rule `{}`", key));
+                };
+                let tuning = self.items.entry(name.to_string()).or_default();
+                match field {
+                    "damage" => tuning.damage = Some(parse_amount(key, value)?),
+                    "durability" => tuning.durability = Some(parse_value(key, value)?),
+                    _ => return Err(format!("unknown item rule `{}`", field)),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// HELPER FUNCTION
+
+/// Helper function to parse the value of a rule
+fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, key))
+}
+
+/**
+Helper function to parse a duration, speed or other amount that can't be negative.
+Durations end up in timers, which panic on negative or NaN seconds.
+*/
+fn parse_amount(key: &str, value: &str) -> Result<f32, String> {
+    parse_value(key, value)
+        .ok()
+        .filter(|amount: &f32| amount.is_finite() && *amount >= 0.0)
+        .ok_or_else(|| format!("invalid value `{}` for `{}`", value, key))
+}
+
+/// Helper function to parse a game mode by the name used in the rules file
+fn parse_mode(value: &str) -> Result<GameMode, String> {
+    match value {
+        "deathmatch" => Ok(GameMode::Deathmatch),
+        "team_deathmatch" => Ok(GameMode::TeamDeathmatch),
+        "last_standing" => Ok(GameMode::LastStanding),
+        "king_of_the_hill" => Ok(GameMode::KingOfTheHill),
+        _ => Err(format!("unknown mode `{}`", value)),
+    }
+}
+
+/// Helper function to parse a bot difficulty by the name used in the rules file
+fn parse_difficulty(value: &str) -> Result<BotDifficulty, String> {
+    match value {
+        "easy" => Ok(BotDifficulty::Easy),
+        "normal" => Ok(BotDifficulty::Normal),
+        "hard" => Ok(BotDifficulty::Hard),
+        _ => Err(format!("unknown bot difficulty `{}`", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::combat::{Detonation, FuseSpec};
+    use crate::components::player::ItemType;
+
+    fn throwable(name: &str, fuse: Option<FuseSpec>) -> Item {
+        Item {
+            name: name.to_string(),
+            description: String::new(),
+            type_: ItemType::Active,
+            effect: ItemEffect::Throw {
+                mesh: Handle::default(),
+                material: Handle::default(),
+                damage: 10.0,
+                speed: 0.0,
+                fuse,
+            },
+            use_time: 0.0,
+            cooldown: None,
+        }
+    }
+
+    fn thrown_speed(item: &Item) -> f32 {
+        match item.effect {
+            ItemEffect::Throw { speed, .. } => speed,
+            _ => panic!("{} is not thrown", item.name),
+        }
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let rules = MatchRules::parse("# Quick matches\n\n  time_limit = 60 # a minute\n   \nbot_slots=2\n").unwrap();
+
+        assert_eq!(rules.time_limit, 60.0);
+        assert_eq!(rules.bot_slots, 2);
+        // Keys that are left out keep their default
+        assert_eq!(rules.respawn_delay, MatchRules::default().respawn_delay);
+    }
+
+    #[test]
+    fn lines_without_a_value_are_rejected() {
+        let error = MatchRules::parse("time_limit = 60\nfriendly_fire\n").unwrap_err();
+
+        assert_eq!(error, "line 2: expected `key = value`");
+    }
+
+    #[test]
+    fn unknown_rules_are_rejected() {
+        assert_eq!(MatchRules::parse("gravity = 1").unwrap_err(), "line 1: unknown rule `gravity`");
+        assert_eq!(MatchRules::parse("item.damage = 1").unwrap_err(), "line 1: unknown rule `item.damage`");
+    }
+
+    #[test]
+    fn item_names_are_split_on_the_last_dot() {
+        let rules = MatchRules::parse("item.Mk. II Sword.damage = 40\nitem.Mk. II Sword.durability = 7").unwrap();
+
+        let tuning = &rules.items["Mk. II Sword"];
+        assert_eq!(tuning.damage, Some(40.0));
+        assert_eq!(tuning.durability, Some(7));
+    }
+
+    #[test]
+    fn unknown_item_rules_are_rejected() {
+        let error = MatchRules::parse("item.Rock.weight = 3").unwrap_err();
+
+        assert_eq!(error, "line 1: unknown item rule `weight`");
+    }
+
+    #[test]
+    fn modes_and_difficulties_are_parsed_by_name() {
+        let rules = MatchRules::parse("mode = team_deathmatch\nbot_difficulty = hard").unwrap();
+        assert_eq!(rules.mode, GameMode::TeamDeathmatch);
+        assert_eq!(rules.bot_difficulty, BotDifficulty::Hard);
+
+        assert_eq!(MatchRules::parse("mode = capture_the_flag").unwrap_err(), "line 1: unknown mode `capture_the_flag`");
+        assert_eq!(MatchRules::parse("bot_difficulty = insane").unwrap_err(), "line 1: unknown bot difficulty `insane`");
+    }
+
+    #[test]
+    fn negative_and_nan_amounts_are_rejected() {
+        assert_eq!(
+            MatchRules::parse("respawn_delay = -1").unwrap_err(),
+            "line 1: invalid value `-1` for `respawn_delay`"
+        );
+        assert_eq!(MatchRules::parse("warmup_time = nan").unwrap_err(), "line 1: invalid value `nan` for `warmup_time`");
+        assert_eq!(MatchRules::parse("throw_speed = inf").unwrap_err(), "line 1: invalid value `inf` for `throw_speed`");
+        assert_eq!(
+            MatchRules::parse("projectile_lifetime = ten").unwrap_err(),
+            "line 1: invalid value `ten` for `projectile_lifetime`"
+        );
+        // Zero is fine, it turns the rule off
+        assert_eq!(MatchRules::parse("spawn_protection = 0").unwrap().spawn_protection, 0.0);
+    }
+
+    #[test]
+    fn throwables_are_thrown_or_lobbed_by_their_fuse() {
+        let rules = MatchRules::parse("throw_speed = 35\nlob_speed = 12\nitem.Rock.damage = 25").unwrap();
+        let fuse = FuseSpec { seconds: 2.0, detonation: Detonation::Explosion { radius: 4.0, damage: 50.0, knockback: 10.0 } };
+
+        let mut rock = throwable("Rock", None);
+        let mut grenade = throwable("Grenade", Some(fuse));
+        rules.tune_item(&mut rock);
+        rules.tune_item(&mut grenade);
+
+        assert_eq!(thrown_speed(&rock), 35.0);
+        assert_eq!(thrown_speed(&grenade), 12.0);
+        assert!(matches!(rock.effect, ItemEffect::Throw { damage, .. } if damage == 25.0));
+        assert!(matches!(grenade.effect, ItemEffect::Throw { damage, .. } if damage == 10.0));
+    }
+}
@@ -0,0 +1,44 @@
+use bevy::color::palettes::css::RED;
+use bevy::prelude::*;
+
+use crate::components::team::Team;
+
+/**
+TeamAssets store the player materials, one for each team
+and one for players without a team.
+ */
+#[derive(Debug, Resource)]
+pub struct TeamAssets {
+    pub red_material: Handle<StandardMaterial>,
+    pub blue_material: Handle<StandardMaterial>,
+    pub neutral_material: Handle<StandardMaterial>,
+}
+
+impl TeamAssets {
+    /// Returns the material a player on the given team is drawn with
+    pub fn material_for(&self, team: Option<&Team>) -> Handle<StandardMaterial> {
+        match team {
+            Some(Team::Red) => self.red_material.clone(),
+            Some(Team::Blue) => self.blue_material.clone(),
+            None => self.neutral_material.clone(),
+        }
+    }
+}
+
+impl FromWorld for TeamAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut material = |color: Color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                ..default()
+            })
+        };
+
+        Self {
+            red_material: material(Team::Red.color()),
+            blue_material: material(Team::Blue.color()),
+            neutral_material: material(Color::from(RED)),
+        }
+    }
+}
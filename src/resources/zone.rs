@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+/// Seconds between two damage ticks for players outside the safe zone
+pub const ZONE_TICK: f32 = 1.0;
+/// How much more damage each tick outside the zone deals than the first, as a fraction of it
+pub const ZONE_ESCALATION: f32 = 0.25;
+
+/// One step of the safe zone closing in
+#[derive(Debug, Clone, Copy)]
+pub struct ZonePhase {
+    /// Seconds the zone holds still before it shrinks
+    pub wait: f32,
+    /// Seconds the shrink takes
+    pub shrink: f32,
+    /// Radius after the shrink, as a fraction of the starting radius
+    pub radius: f32,
+    /// Damage of the first tick outside the zone during this phase
+    pub damage: f32,
+}
+
+/// The phases of a match, the last one closes the zone completely
+pub const ZONE_PHASES: [ZonePhase; 4] = [
+    ZonePhase { wait: 45.0, shrink: 30.0, radius: 0.6, damage: 2.0 },
+    ZonePhase { wait: 30.0, shrink: 25.0, radius: 0.35, damage: 4.0 },
+    ZonePhase { wait: 25.0, shrink: 20.0, radius: 0.15, damage: 8.0 },
+    ZonePhase { wait: 20.0, shrink: 15.0, radius: 0.0, damage: 15.0 },
+];
+
+/**
+The circle players have to stay inside in last player standing matches.
+Each phase it waits, then moves and shrinks towards a smaller circle inside the current one.
+ */
+#[derive(Debug, Default, Resource)]
+pub struct SafeZone {
+    /// Whether the zone is closing in and hurting players this match
+    pub active: bool,
+    /// Center on the ground, `x` and `z` in world space
+    pub center: Vec2,
+    pub radius: f32,
+    /// Radius at the start of the match, the phases shrink relative to it
+    pub start_radius: f32,
+    /// Half the size of the arena, the center never leaves it
+    pub bounds: Vec2,
+    /// Index into [`ZONE_PHASES`] of the current phase, past the end once the zone is closed
+    pub phase: usize,
+    /// Whether the current phase is shrinking rather than waiting
+    pub shrinking: bool,
+    /// Circle the current shrink started from
+    pub from: (Vec2, f32),
+    /// Circle the current shrink ends at
+    pub to: (Vec2, f32),
+    /// Time left waiting or shrinking in the current phase
+    pub timer: Timer,
+    pub damage_timer: Timer,
+}
+
+impl SafeZone {
+    /// Starts a new zone covering all of an arena of the given size, corners included
+    pub fn start(&mut self, width: f32, height: f32) {
+        let bounds = Vec2::new(width, height) / 2.0;
+        let radius = bounds.length();
+        *self = SafeZone {
+            active: true,
+            center: Vec2::ZERO,
+            radius,
+            start_radius: radius,
+            bounds,
+            phase: 0,
+            shrinking: false,
+            from: (Vec2::ZERO, radius),
+            to: (Vec2::ZERO, radius),
+            timer: Timer::from_seconds(ZONE_PHASES[0].wait, TimerMode::Once),
+            damage_timer: Timer::from_seconds(ZONE_TICK, TimerMode::Repeating),
+        };
+    }
+
+    pub fn contains(&self, position: Vec3) -> bool {
+        position.xz().distance(self.center) <= self.radius
+    }
+
+    /// Damage of the first tick outside the zone right now
+    pub fn damage(&self) -> f32 {
+        ZONE_PHASES[self.phase.min(ZONE_PHASES.len() - 1)].damage
+    }
+
+    /// Whether the zone has closed completely and won't shrink anymore
+    pub fn is_closed(&self) -> bool {
+        self.phase >= ZONE_PHASES.len()
+    }
+}
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use crate::components::combat::{AreaEffect, FieldKind};
+use crate::resources::item::ItemAssets;
+
+/// Spawns a timed volume centered on `position` that applies its status to players inside
+pub fn spawn_area_effect(
+    commands: &mut Commands,
+    item_assets: &ItemAssets,
+    kind: FieldKind,
+    radius: f32,
+    duration: f32,
+    position: Vec3,
+) -> Entity {
+    let material = match kind {
+        FieldKind::Smoke => item_assets.smoke_material.clone(),
+        FieldKind::Slow { .. } => item_assets.slow_field_material.clone(),
+    };
+    // The shared item mesh has a radius of 0.5
+    let scale = Vec3::splat(radius * 2.0);
+
+    commands
+        .spawn((
+            Transform::from_translation(position).with_scale(scale),
+            Mesh3d(item_assets.mesh.clone()),
+            MeshMaterial3d(material),
+            AreaEffect {
+                kind,
+                radius,
+                timer: Timer::from_seconds(duration, TimerMode::Once),
+            },
+        ))
+        .id()
+}
@@ -1,7 +1,7 @@
 use crate::resources::game::WorldAttribute;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
-use crate::components::world::{AabbCollider, Ground};
+use crate::components::world::{AabbCollider, Ground, WORLD_GROUP};
 
 pub fn spawn_ground(
     commands: &mut Commands,
@@ -20,6 +20,7 @@ pub fn spawn_ground(
         })),
         Collider::cuboid(width / 2., 0.25, height / 2.),
         RigidBody::Fixed,
+        CollisionGroups::new(WORLD_GROUP, Group::ALL),
         Ground, // a component to tag this entity as ground
         AabbCollider {
             half_extents: Vec3::new(width / 2.0, 0.125, height / 2.0),
@@ -1,9 +1,13 @@
-use bevy::color::palettes::tailwind::*;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-use crate::components::player::{Item, ItemEffect, ItemType, SpeedBoost, Weapon};
-use crate::components::world::ThrewObject;
+use crate::components::combat::{DamageType, Detonation, FieldKind, FuseSpec};
+use crate::components::player::{
+    AmmoType, CooldownScope, FireMode, Item, ItemCooldown, ItemEffect, ItemType, PickupDelay,
+    RangedWeapon, StatModifier, Weapon, WeaponSlotKind,
+};
+use crate::resources::item::ItemAssets;
+use crate::resources::rules::MatchRules;
 
 #[derive(Bundle, Clone)]
 pub struct IncreaseSpeedBundle {
@@ -28,11 +32,13 @@ impl IncreaseSpeedBundle {
             item: Item {
                 name: String::from("Increase Speed"),
                 description: String::from("Add increasing speed for amount of time"),
-                type_: ItemType::Passive,
+                type_: ItemType::Active,
                 effect: ItemEffect::IncreaseSpeed {
                     amount: (10.0),
                     duration: (10.0),
                 },
+                use_time: 0.0,
+                cooldown: None,
             },
             collider,
             sensor: Sensor::default(),
@@ -43,39 +49,143 @@ impl IncreaseSpeedBundle {
     }
 }
 
+/// Spawns an item into the world as a pickup at the given position
+pub fn spawn_item_pickup(
+    commands: &mut Commands,
+    item_assets: &ItemAssets,
+    item: Item,
+    position: Vec3,
+) -> Entity {
+    let material = item_assets.material_for(&item);
+    commands
+        .spawn((
+            Transform::from_translation(position),
+            Mesh3d(item_assets.mesh.clone()),
+            Collider::ball(0.5),
+            MeshMaterial3d(material),
+            item,
+            Sensor::default(),
+            CollisionGroups::new(Group::GROUP_2, Group::GROUP_1),
+            ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC,
+        ))
+        .id()
+}
+
+/// Spawns an item that was dropped by a player.
+/// It can't be picked up again for a short moment.
+pub fn spawn_dropped_item(
+    commands: &mut Commands,
+    item_assets: &ItemAssets,
+    item: Item,
+    position: Vec3,
+) -> Entity {
+    let entity = spawn_item_pickup(commands, item_assets, item, position);
+    commands
+        .entity(entity)
+        .insert(PickupDelay(Timer::from_seconds(1.0, TimerMode::Once)));
+    entity
+}
+
+/// Creates a passive item that applies its modifier while held
+fn passive_item(name: &str, description: &str, modifier: StatModifier) -> Item {
+    Item {
+        name: String::from(name),
+        description: String::from(description),
+        type_: ItemType::Passive,
+        effect: ItemEffect::Passive(modifier),
+        use_time: 0.0,
+        cooldown: None,
+    }
+}
+
+/// Creates an ammo item for ranged weapons
+fn ammo_item(name: &str, ammo_type: AmmoType, amount: u16) -> Item {
+    Item {
+        name: String::from(name),
+        description: format!("{} {:?} ammo", amount, ammo_type),
+        type_: ItemType::Active,
+        effect: ItemEffect::Ammo { ammo_type, amount },
+        use_time: 0.0,
+        cooldown: None,
+    }
+}
+
+/// Creates an armor item, it takes a while to put on
+fn armor_item(name: &str, description: &str, amount: f32, absorption: f32, use_time: f32) -> Item {
+    Item {
+        name: String::from(name),
+        description: String::from(description),
+        type_: ItemType::Active,
+        effect: ItemEffect::Armor { amount, absorption },
+        use_time,
+        cooldown: None,
+    }
+}
+
+/// Creates a throwable item that goes off after its fuse runs out
+fn throwable_item(
+    name: &str,
+    description: &str,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    speed: f32,
+    fuse: FuseSpec,
+) -> Item {
+    Item {
+        name: String::from(name),
+        description: String::from(description),
+        type_: ItemType::Active,
+        effect: ItemEffect::Throw {
+            mesh,
+            material,
+            damage: 5.0,
+            speed,
+            fuse: Some(fuse),
+        },
+        use_time: 0.0,
+        cooldown: Some(ItemCooldown {
+            duration: 0.75,
+            scope: CooldownScope::Category,
+        }),
+    }
+}
+
 pub fn spawn_items(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    item_assets: Res<ItemAssets>,
+    rules: Res<MatchRules>,
 ) {
     let spawn_positions = [
         Vec3::new(-15.0, 0.75, 15_f32),
         Vec3::new(15.0, 0.75, -15_f32),
         Vec3::new(-35.0, 0.75, 30_f32),
         Vec3::new(25.0, 0.75, -25_f32),
+        Vec3::new(-25.0, 0.75, -10_f32),
+        Vec3::new(35.0, 0.75, 5_f32),
+        Vec3::new(-5.0, 0.75, 35_f32),
+        Vec3::new(5.0, 0.75, -35_f32),
+        Vec3::new(-40.0, 0.75, -35_f32),
+        Vec3::new(40.0, 0.75, 35_f32),
+        Vec3::new(-40.0, 0.75, 5_f32),
+        Vec3::new(0.0, 0.75, 25_f32),
+        Vec3::new(25.0, 0.75, 0_f32),
+        Vec3::new(-10.0, 0.75, 25_f32),
+        Vec3::new(30.0, 0.75, -5_f32),
+        Vec3::new(-20.0, 0.75, -25_f32),
+        Vec3::new(-30.0, 0.75, 20_f32),
+        Vec3::new(15.0, 0.75, 30_f32),
+        Vec3::new(-45.0, 0.75, -20_f32),
+        Vec3::new(45.0, 0.75, 20_f32),
+        Vec3::new(-15.0, 0.75, -40_f32),
     ];
 
-    let mesh = meshes.add(Sphere::new(0.5));
-    let material = materials.add(StandardMaterial {
-        base_color: Color::from(YELLOW_300),
-        ..default()
-    });
-
-    let throw_item_material = materials.add(StandardMaterial {
-        base_color: Color::from(GREEN_400),
-        ..default()
-    });
-
-    let melee_weapon_material = materials.add(StandardMaterial {
-        base_color: Color::from(CYAN_400),
-        ..default()
-    });
+    let mesh = item_assets.mesh.clone();
+
     // spawn the items
     commands.spawn((
         IncreaseSpeedBundle::new(
             Mesh3d(mesh.clone()),
-            MeshMaterial3d(material.clone()),
+            MeshMaterial3d(item_assets.buff_material.clone()),
             Collider::ball(0.5),
         ),
         Transform::from_translation(spawn_positions[0]),
@@ -84,35 +194,105 @@ pub fn spawn_items(
     commands.spawn((
         IncreaseSpeedBundle::new(
             Mesh3d(mesh.clone()),
-            MeshMaterial3d(material.clone()),
+            MeshMaterial3d(item_assets.buff_material.clone()),
             Collider::ball(0.5),
         ),
         Transform::from_translation(spawn_positions[1]),
     ));
 
     // spawn throw item
-    commands.spawn((
-        Transform::from_translation(spawn_positions[2]),
-        Mesh3d(mesh.clone()),
-        Collider::ball(0.5),
-        MeshMaterial3d(throw_item_material.clone()),
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
         Item {
             name: String::from("Rock"),
             description: String::from("Batu buat lu lempar"),
             type_: ItemType::Active,
-            effect: ItemEffect::Throw(mesh.clone(), throw_item_material.clone()),
+            effect: ItemEffect::Throw {
+                mesh: mesh.clone(),
+                material: item_assets.throw_material.clone(),
+                damage: 10.0,
+                speed: rules.throw_speed,
+                fuse: None,
+            },
+            use_time: 0.0,
+            // Shared by every throwable so a stack of rocks can't be spammed
+            cooldown: Some(ItemCooldown {
+                duration: 0.75,
+                scope: CooldownScope::Category,
+            }),
         },
-        Sensor::default(),
-        CollisionGroups::new(Group::GROUP_2, Group::GROUP_1),
-        ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC,
-    ));
+        spawn_positions[2],
+    );
+
+    // spawn area effect throwables
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        throwable_item(
+            "Granat",
+            "Meledak setelah dua detik",
+            mesh.clone(),
+            item_assets.explosive_material.clone(),
+            rules.lob_speed,
+            FuseSpec {
+                seconds: 2.0,
+                detonation: Detonation::Explosion {
+                    radius: 6.0,
+                    damage: 60.0,
+                    knockback: 15.0,
+                },
+            },
+        ),
+        spawn_positions[15],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        throwable_item(
+            "Bom Asap",
+            "Asap tebal buat sembunyi",
+            mesh.clone(),
+            item_assets.smoke_material.clone(),
+            rules.lob_speed,
+            FuseSpec {
+                seconds: 1.5,
+                detonation: Detonation::Field {
+                    kind: FieldKind::Smoke,
+                    radius: 5.0,
+                    duration: 8.0,
+                },
+            },
+        ),
+        spawn_positions[16],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        throwable_item(
+            "Lem Tikus",
+            "Bikin lawan jalan pelan",
+            mesh.clone(),
+            item_assets.slow_field_material.clone(),
+            rules.lob_speed,
+            FuseSpec {
+                seconds: 1.0,
+                detonation: Detonation::Field {
+                    kind: FieldKind::Slow { factor: 0.4 },
+                    radius: 4.0,
+                    duration: 6.0,
+                },
+            },
+        ),
+        spawn_positions[17],
+    );
 
     // spawn melee weapon item
-    commands.spawn((
-        Transform::from_translation(spawn_positions[3]),
-        Mesh3d(mesh.clone()),
-        Collider::ball(0.5),
-        MeshMaterial3d(melee_weapon_material.clone()),
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
         Item {
             name: "Celurit".into(),
             description: String::from("Senggol Bacok"),
@@ -121,11 +301,218 @@ pub fn spawn_items(
                 name: String::from("Celurit"),
                 description: String::from("Senggol Bacok"),
                 throwable: false,
-                durability: 5
-            })
+                durability: 5,
+                damage: 20.0,
+                damage_type: DamageType::Physical,
+                knockback: 3.0,
+                hit_stun: 0.0,
+                slot: WeaponSlotKind::Melee,
+                ranged: None,
+            }),
+            use_time: 0.0,
+            cooldown: None,
+        },
+        spawn_positions[3],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        Weapon {
+            name: String::from("Golok"),
+            description: String::from("Tajam dan berat"),
+            throwable: false,
+            durability: 8,
+            damage: 30.0,
+            damage_type: DamageType::Physical,
+            knockback: 6.0,
+            hit_stun: 0.3,
+            slot: WeaponSlotKind::Melee,
+            ranged: None,
+        }
+        .into_item(),
+        spawn_positions[9],
+    );
+
+    // spawn throwable weapon
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        Weapon {
+            name: String::from("Pisau Lempar"),
+            description: String::from("Lempar, pungut, lempar lagi"),
+            throwable: true,
+            durability: 4,
+            damage: 25.0,
+            damage_type: DamageType::Physical,
+            knockback: 4.0,
+            hit_stun: 0.0,
+            slot: WeaponSlotKind::Secondary,
+            ranged: None,
+        }
+        .into_item(),
+        spawn_positions[10],
+    );
+
+    // spawn ranged weapons and their ammo
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        Weapon {
+            name: String::from("Senapan Angin"),
+            description: String::from("Tembak lurus, kena langsung"),
+            throwable: false,
+            durability: 1,
+            damage: 18.0,
+            damage_type: DamageType::Piercing,
+            knockback: 1.0,
+            hit_stun: 0.0,
+            slot: WeaponSlotKind::Primary,
+            ranged: Some(RangedWeapon {
+                fire_mode: FireMode::Hitscan { range: 80.0 },
+                ammo_type: AmmoType::Pellet,
+                magazine_size: 10,
+                magazine: 10,
+                reserve_ammo: 20,
+                reload_time: 1.5,
+                fire_rate: 3.0,
+                spread: 0.01,
+                fire_cooldown: 0.0,
+            }),
+        }
+        .into_item(),
+        spawn_positions[11],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        Weapon {
+            name: String::from("Ketapel"),
+            description: String::from("Kerikilnya jatuh kalau jauh"),
+            throwable: false,
+            durability: 1,
+            damage: 15.0,
+            damage_type: DamageType::Physical,
+            knockback: 3.0,
+            hit_stun: 0.0,
+            slot: WeaponSlotKind::Secondary,
+            ranged: Some(RangedWeapon {
+                fire_mode: FireMode::Projectile { speed: 45.0, gravity_scale: 1.0 },
+                ammo_type: AmmoType::Pebble,
+                magazine_size: 5,
+                magazine: 5,
+                reserve_ammo: 10,
+                reload_time: 1.0,
+                fire_rate: 2.0,
+                spread: 0.03,
+                fire_cooldown: 0.0,
+            }),
+        }
+        .into_item(),
+        spawn_positions[12],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        ammo_item("Peluru Angin", AmmoType::Pellet, 20),
+        spawn_positions[13],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        ammo_item("Kerikil", AmmoType::Pebble, 10),
+        spawn_positions[14],
+    );
+
+    // spawn heal item
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        Item {
+            name: String::from("Nasi Bungkus"),
+            description: String::from("Makan dulu biar kuat"),
+            type_: ItemType::Active,
+            effect: ItemEffect::Heal(30.0),
+            use_time: 2.0,
+            cooldown: Some(ItemCooldown {
+                duration: 5.0,
+                scope: CooldownScope::Category,
+            }),
         },
-        Sensor::default(),
-        CollisionGroups::new(Group::GROUP_2, Group::GROUP_1),
-        ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC,
-        ));
+        spawn_positions[4],
+    );
+
+    // spawn passive items
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        passive_item(
+            "Sendal Swallow",
+            "Lari lebih kencang selama dibawa",
+            StatModifier { move_speed: 3.0, ..default() },
+        ),
+        spawn_positions[5],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        passive_item(
+            "Jimat",
+            "Menambah darah maksimal",
+            StatModifier { max_health: 25.0, ..default() },
+        ),
+        spawn_positions[6],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        passive_item(
+            "Helm Proyek",
+            "Mengurangi damage yang diterima",
+            StatModifier { damage_resistance: 0.15, ..default() },
+        ),
+        spawn_positions[7],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        passive_item(
+            "Magnet",
+            "Menarik item dari kejauhan",
+            StatModifier { pickup_radius: 4.0, ..default() },
+        ),
+        spawn_positions[8],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        passive_item(
+            "Baterai",
+            "Armor pulih sendiri saat aman",
+            StatModifier { armor_regen: 5.0, ..default() },
+        ),
+        spawn_positions[20],
+    );
+
+    // spawn armor items
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        armor_item("Rompi", "Menahan sebagian damage", 50.0, 0.6, 1.5),
+        spawn_positions[18],
+    );
+
+    spawn_item_pickup(
+        &mut commands,
+        &item_assets,
+        armor_item("Perisai Energi", "Menahan semua damage sampai habis", 100.0, 1.0, 2.5),
+        spawn_positions[19],
+    );
 }
@@ -5,4 +5,9 @@ pub mod player;
 pub mod ui;
 pub mod wall;
 pub mod structures;
-pub mod item;
\ No newline at end of file
+pub mod item;
+pub mod projectile;
+pub mod area;
+pub mod spawn_point;
+pub mod zone;
+pub mod objective;
\ No newline at end of file
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::components::objective::{CaptureZone, HILL_HEIGHT, HILL_RADIUS};
+use crate::components::world::{EntityName, TriggerVolume, TRIGGER_GROUP};
+
+/**
+Spawns the hill for king of the hill matches.
+It moves between the tops of the two boxes and an open spot on the ground, and stays hidden in other modes.
+*/
+pub fn spawn_capture_zone(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    // The boxes from `spawn_boxes` are 2 high and centered 1.5 above the ground
+    let positions = vec![
+        Vec3::new(10.5, 2.5, -10.5),
+        Vec3::new(20.0, 2.5, 20.0),
+        Vec3::new(-15.0, 0.0, 10.0),
+    ];
+    let zone = CaptureZone::new(positions);
+
+    commands.spawn((
+        Transform::from_translation(zone.center()),
+        Mesh3d(meshes.add(Cylinder::new(HILL_RADIUS, HILL_HEIGHT))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(1.0, 1.0, 1.0, 0.25),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            cull_mode: None,
+            double_sided: true,
+            ..default()
+        })),
+        Visibility::Hidden,
+        EntityName::new("Hill"),
+        Collider::cylinder(HILL_HEIGHT / 2.0, HILL_RADIUS),
+        Sensor,
+        CollisionGroups::new(TRIGGER_GROUP, Group::GROUP_1),
+        ActiveEvents::COLLISION_EVENTS,
+        TriggerVolume::default(),
+        zone,
+    ));
+}
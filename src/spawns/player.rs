@@ -1,10 +1,25 @@
+use crate::components::bot::Bot;
 use crate::components::camera::{CameraSensitivity, PlayerCamera};
-use crate::components::player::{Direction, Inventory, JumpAbility, Player, PlayerStats};
-use crate::components::world::EntityName;
+use crate::components::combat::{
+    Blocking, Eliminated, InCombat, Obscured, ParryCooldown, Respawning, Slowed, SpawnProtection, SpawnProtectionBubble,
+    Staggered, Stunned,
+};
+use crate::components::player::{
+    AppliedModifiers, Direction, Inventory, ItemChannel, ItemCooldowns, JumpAbility, LocalPlayer, Player, PlayerIntent,
+    PlayerStats, Reloading, SpeedBoost, WeaponSwitch,
+};
+use crate::components::prediction::PredictedMovement;
+use crate::components::score::{RecentAttackers, Score};
+use crate::components::world::{EntityName, OutsideZone, TRIGGER_GROUP, WORLD_GROUP};
+use crate::resources::item::ItemAssets;
+use crate::resources::rules::MatchRules;
 use bevy::color::palettes::css::RED;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
+/// Where the player starts and returns to when a match resets
+pub const PLAYER_SPAWN_POSITION: Vec3 = Vec3::new(0.0, 5.0, 0.0);
+
 #[derive(Bundle)]
 struct PlayerBundle {
     player: Player,
@@ -26,24 +41,129 @@ struct PlayerBundle {
     jump_ability: JumpAbility,
 }
 
+/// Names given to bots, the first one not taken is used
+pub const BOT_NAMES: [&str; 8] = ["Himmel", "Heiter", "Eisen", "Fern", "Stark", "Sein", "Denken", "Ubel"];
+
+/// Spawns the player controlled on this machine, with the camera they look through
 pub fn spawn_player(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
+    rules: &MatchRules,
+) {
+    let player = spawn_player_body(commands, meshes, materials, rules, "Frieren", PLAYER_SPAWN_POSITION);
+    commands
+        .entity(player)
+        .insert((
+            LocalPlayer,
+            PredictedMovement {
+                last_position: PLAYER_SPAWN_POSITION,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Transform::from_xyz(0., 1.5, 0.),
+                GlobalTransform::default(),
+                PlayerCamera,
+                Camera3d::default(),
+                Projection::from(PerspectiveProjection {
+                    fov: 80_f32.to_radians(),
+                    ..Default::default()
+                }),
+            ));
+        });
+}
+
+/// Spawns a bot player at `position`, playing at the bot difficulty of the match rules
+pub fn spawn_bot(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    rules: &MatchRules,
+    name: &str,
+    position: Vec3,
+) {
+    let bot = spawn_player_body(commands, meshes, materials, rules, name, position);
+    commands.entity(bot).insert(Bot::new(rules.bot_difficulty));
+}
+
+/// Stats every player starts a match with
+pub fn starting_stats(rules: &MatchRules) -> PlayerStats {
+    PlayerStats {
+        health: rules.starting_health,
+        max_health: rules.starting_health,
+        speed: rules.move_speed,
+        ..Default::default()
+    }
+}
+
+/**
+Puts a player back into the arena at `transform` with fresh stats and an empty inventory.
+Their score is kept, and they can't be damaged for a few seconds.
+*/
+pub fn respawn_player(
+    commands: &mut Commands,
+    item_assets: &ItemAssets,
+    rules: &MatchRules,
+    player: Entity,
+    transform: Transform,
 ) {
+    commands
+        .entity(player)
+        .insert((
+            starting_stats(rules),
+            GravityScale(rules.gravity_scale),
+            PlayerIntent::default(),
+            Inventory::new(),
+            ItemCooldowns::default(),
+            AppliedModifiers::default(),
+            RecentAttackers::default(),
+            transform,
+            Velocity::zero(),
+            Visibility::Inherited,
+            SpawnProtection {
+                timer: Timer::from_seconds(rules.spawn_protection, TimerMode::Once),
+            },
+        ))
+        .remove::<(Respawning, Eliminated, OutsideZone, ColliderDisabled, RigidBodyDisabled)>()
+        .remove::<(SpeedBoost, ItemChannel, Reloading, WeaponSwitch, Blocking)>()
+        .remove::<(Staggered, Stunned, InCombat, Slowed, Obscured, ParryCooldown)>()
+        .with_children(|parent| {
+            parent.spawn((
+                // The shared item mesh has a radius of 0.5, this wraps around the player's cube
+                Transform::from_scale(Vec3::splat(2.2)),
+                Mesh3d(item_assets.mesh.clone()),
+                MeshMaterial3d(item_assets.spawn_protection_material.clone()),
+                SpawnProtectionBubble,
+            ));
+        });
+}
+
+// HELPER FUNCTION
+
+/// Helper function to spawn everything a player is made of, whoever controls them
+fn spawn_player_body(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    rules: &MatchRules,
+    name: &str,
+    position: Vec3,
+) -> Entity {
     commands
         .spawn(PlayerBundle {
             player: Player,
-            name: EntityName::new("Frieren"),
+            name: EntityName::new(name),
             global_transform: GlobalTransform::default(),
-            transform: Transform::from_xyz(0.0, 5., 0.0),
+            transform: Transform::from_translation(position),
             visibility: Visibility::default(),
             mesh: Mesh3d(meshes.add(Cuboid::default())),
             material: MeshMaterial3d(materials.add(StandardMaterial {
                 base_color: RED.into(),
                 ..Default::default()
             })),
-            movement: PlayerStats { speed: 15.0, ..Default::default() },
+            movement: starting_stats(rules),
             camera_sensitivity: CameraSensitivity::default(),
             collider: Collider::cuboid(0.5, 0.5, 0.5),
             rigid_body: RigidBody::Dynamic,
@@ -53,29 +173,23 @@ pub fn spawn_player(
                 angvel: Vec3::ZERO,
             },
             friction: Friction::coefficient(0.0),
-            gravity_scale: GravityScale(2.0),
+            gravity_scale: GravityScale(rules.gravity_scale),
             locked_axes: LockedAxes::ROTATION_LOCKED,
             jump_ability: JumpAbility::default(),
         })
+        .insert(PlayerIntent::default())
         .insert(ExternalImpulse::default())
         .insert(ActiveEvents::COLLISION_EVENTS)
         .insert(ActiveCollisionTypes::default())
         .insert(Inventory::new())
+        .insert(ItemCooldowns::default())
+        .insert(AppliedModifiers::default())
+        .insert(Score::default())
+        .insert(RecentAttackers::default())
         .insert(CollisionGroups::new(
             Group::GROUP_1,
-            Group::GROUP_2 | Group::GROUP_3,
+            Group::GROUP_2 | Group::GROUP_3 | WORLD_GROUP | TRIGGER_GROUP,
         ))
         .insert(Direction::default())
-        .with_children(|parent| {
-            parent.spawn((
-                Transform::from_xyz(0., 1.5, 0.),
-                GlobalTransform::default(),
-                PlayerCamera,
-                Camera3d::default(),
-                Projection::from(PerspectiveProjection {
-                    fov: 80_f32.to_radians(),
-                    ..Default::default()
-                }),
-            ));
-        });
-}
\ No newline at end of file
+        .id()
+}
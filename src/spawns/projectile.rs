@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::components::combat::{DamageSource, ThrowParams};
+use crate::components::world::{ClearingThrower, ThrewObject, WORLD_GROUP};
+
+/**
+Spawns an object thrown from `origin` in `direction` and returns its entity.
+The object is solid, it collides with players and the world but not with items.
+It only starts pushing players after [`THROWER_GRACE_TIME`](crate::components::world::THROWER_GRACE_TIME), so it can't shove its thrower.
+It carries `source` so the thrower is credited for hits even after they are gone.
+*/
+pub fn spawn_thrown_object(
+    commands: &mut Commands,
+    params: ThrowParams,
+    source: DamageSource,
+    origin: Vec3,
+    direction: Vec3,
+    spawn_time: f32,
+) -> Entity {
+    // Distance from the thrower's center that keeps the object clear of their collider
+    const THROWER_CLEARANCE: f32 = 0.8;
+
+    let position = origin + Vec3::Y + direction * (THROWER_CLEARANCE + params.radius);
+    commands
+        .spawn((
+            Velocity {
+                linvel: params.speed * direction,
+                angvel: params.spin,
+            },
+            CollisionGroups::new(
+                Group::GROUP_3,
+                Group::GROUP_1 | WORLD_GROUP,
+            ),
+            // Players are still hit, but not pushed until the object is clear of its thrower
+            SolverGroups::new(Group::GROUP_3, WORLD_GROUP),
+            ClearingThrower,
+            ActiveEvents::COLLISION_EVENTS,
+            ThrewObject {
+                spawn_time,
+                damage: params.damage,
+                damage_type: params.damage_type,
+                knockback: params.knockback,
+                hit_stun: params.hit_stun,
+                has_hit: false,
+            },
+            source,
+            RigidBody::Dynamic,
+            GravityScale(params.gravity_scale),
+            Restitution {
+                coefficient: params.restitution,
+                combine_rule: CoefficientCombineRule::Max,
+            },
+            // Fast and small objects would otherwise pass through thin walls
+            Ccd::enabled(),
+            Transform::from_translation(position),
+            Collider::ball(params.radius),
+            Mesh3d(params.mesh),
+            MeshMaterial3d(params.material),
+        ))
+        .id()
+}
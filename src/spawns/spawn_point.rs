@@ -0,0 +1,29 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+use bevy::prelude::*;
+
+use crate::components::team::Team;
+use crate::components::world::SpawnPoint;
+
+/**
+Places the spawn points of the arena, around the edges and facing inwards.
+The north side belongs to the red team and the south side to the blue team.
+*/
+pub fn spawn_spawn_points(commands: &mut Commands) {
+    let spawn_points = [
+        (Vec3::new(-40.0, 1.5, -40.0), -3.0 * PI / 4.0, Some(Team::Red)),
+        (Vec3::new(40.0, 1.5, -40.0), 3.0 * PI / 4.0, Some(Team::Red)),
+        (Vec3::new(0.0, 1.5, -42.0), PI, Some(Team::Red)),
+        (Vec3::new(-40.0, 1.5, 40.0), -PI / 4.0, Some(Team::Blue)),
+        (Vec3::new(40.0, 1.5, 40.0), PI / 4.0, Some(Team::Blue)),
+        (Vec3::new(0.0, 1.5, 42.0), 0.0, Some(Team::Blue)),
+        (Vec3::new(-42.0, 1.5, 0.0), -FRAC_PI_2, None),
+        (Vec3::new(42.0, 1.5, 0.0), FRAC_PI_2, None),
+    ];
+
+    for (position, yaw, team) in spawn_points {
+        commands.spawn((
+            Transform::from_translation(position).with_rotation(Quat::from_rotation_y(yaw)),
+            SpawnPoint { team },
+        ));
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
-use crate::components::world::{AabbCollider, Ground, EntityName, NotGround, Structure};
+use crate::components::world::{AabbCollider, Ground, EntityName, NotGround, Structure, WORLD_GROUP};
 
 pub fn spawn_box(
     commands: &mut Commands,
@@ -36,6 +36,7 @@ pub fn spawn_box(
             Ground,
             Collider::cuboid(size.x/2., size.y/2., size.z/2.),
             RigidBody::Fixed,
+            CollisionGroups::new(WORLD_GROUP, Group::ALL),
             AabbCollider {
                     half_extents: Vec3::new(size.x / 2.0, size.y/2., size.z / 2.0),
                 }
@@ -1,6 +1,7 @@
+use crate::components::player::WeaponSlotKind;
 use crate::components::ui::*;
 use bevy::color::palettes::css::ORANGE;
-use bevy::color::palettes::tailwind::BLUE_300;
+use bevy::color::palettes::tailwind::{GRAY_400, GREEN_400, RED_500, SKY_400};
 use bevy::prelude::*;
 
 pub fn setup_debug_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
@@ -54,6 +55,32 @@ pub fn setup_debug_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 pub fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Spawn the damage vignette first so it is drawn below the rest of the HUD
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            position_type: PositionType::Absolute,
+            border: UiRect::all(Val::Px(40.)),
+            ..default()
+        },
+        BorderColor(Color::NONE),
+        DamageVignette::default(),
+    ));
+
+    // Damage indicators are placed around this point at the center of the screen
+    commands.spawn((
+        Node {
+            width: Val::Px(0.),
+            height: Val::Px(0.),
+            position_type: PositionType::Absolute,
+            left: Val::Percent(50.),
+            top: Val::Percent(50.),
+            ..default()
+        },
+        DamageIndicatorAnchor,
+    ));
+
     // spawn crosshair
     commands
         .spawn(Node {
@@ -100,6 +127,66 @@ pub fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                         BackgroundColor(Color::WHITE.into()),
                     ));
                 });
+
+            // Hit marker, a dot at each corner of the crosshair shown when an attack lands
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(36.),
+                        height: Val::Px(36.),
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    Visibility::Hidden,
+                    HitMarker {
+                        timer: Timer::from_seconds(0.0, TimerMode::Once),
+                        kill: false,
+                    },
+                ))
+                .with_children(|marker| {
+                    for (horizontal, vertical) in [(true, true), (true, false), (false, true), (false, false)] {
+                        marker.spawn((
+                            Node {
+                                width: Val::Px(5.),
+                                height: Val::Px(5.),
+                                position_type: PositionType::Absolute,
+                                left: if horizontal { Val::Px(0.) } else { Val::Auto },
+                                right: if horizontal { Val::Auto } else { Val::Px(0.) },
+                                top: if vertical { Val::Px(0.) } else { Val::Auto },
+                                bottom: if vertical { Val::Auto } else { Val::Px(0.) },
+                                ..default()
+                            },
+                            BackgroundColor(Color::WHITE),
+                            HitMarkerDot,
+                        ));
+                    }
+                });
+
+            // Item channel progress bar, hidden until an item is being used
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(120.),
+                        height: Val::Px(8.),
+                        position_type: PositionType::Absolute,
+                        top: Val::Percent(55.),
+                        display: Display::None,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.5)),
+                    ItemChannelBar,
+                ))
+                .with_children(|bar| {
+                    bar.spawn((
+                        Node {
+                            width: Val::Percent(0.),
+                            height: Val::Percent(100.),
+                            ..default()
+                        },
+                        BackgroundColor(Color::from(GREEN_400)),
+                        ItemChannelBarFill,
+                    ));
+                });
         });
 
     // spawn inventory slot
@@ -126,11 +213,262 @@ pub fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                     BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.5).into()),
                     BorderColor(Color::WHITE.into()),
                     InventorySlot {slot}
-                ));
+                )).with_children(|slot_node| {
+                    // Cooldown overlay, drawn above the item image
+                    slot_node.spawn((
+                        Node {
+                            width: Val::Percent(100.),
+                            height: Val::Percent(0.),
+                            position_type: PositionType::Absolute,
+                            bottom: Val::Px(0.),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+                        ZIndex(1),
+                        InventorySlotCooldown { slot },
+                    ));
+                });
             }
         });
 
-    // Spawn weapon slot
+    // Spawn health and armor bars at the bottom center
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.),
+            width: Val::Percent(100.),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(4.),
+            ..default()
+        })
+        .with_children(|parent| {
+            for (vital, color) in [(Vital::Armor, SKY_400), (Vital::Health, RED_500)] {
+                parent
+                    .spawn((
+                        Node {
+                            width: Val::Px(220.),
+                            height: Val::Px(18.),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.5)),
+                    ))
+                    .with_children(|bar| {
+                        bar.spawn((
+                            Node {
+                                width: Val::Percent(0.),
+                                height: Val::Percent(100.),
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(0.),
+                                ..default()
+                            },
+                            BackgroundColor(Color::from(color)),
+                            VitalBarFill { vital },
+                        ));
+                        bar.spawn((
+                            Text::new(""),
+                            TextFont {
+                                font: asset_server.load("fonts/OpenSans.ttf"),
+                                font_size: 14.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                            ZIndex(1),
+                            VitalText { vital },
+                        ));
+                    });
+            }
+        });
+
+    // Spawn notification list below the top center of the screen
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(15.),
+            width: Val::Percent(100.),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(4.),
+            ..default()
+        },
+        NotificationList,
+    ));
+
+    // Spawn kill feed in the top right corner
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            right: Val::Px(10.),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexEnd,
+            row_gap: Val::Px(4.),
+            ..default()
+        },
+        KillFeed,
+    ));
+
+    // Spawn match clock and safe zone timer at the top center
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            width: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load("fonts/OpenSans.ttf"),
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                MatchTimerText,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load("fonts/OpenSans.ttf"),
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                SafeZoneText,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load("fonts/OpenSans.ttf"),
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                CaptureText,
+            ));
+            // Hill bar, hidden unless the match is played for the hill
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(200.),
+                        height: Val::Px(8.),
+                        margin: UiRect::top(Val::Px(4.)),
+                        display: Display::None,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.5)),
+                    CaptureBar,
+                ))
+                .with_children(|bar| {
+                    bar.spawn((
+                        Node {
+                            width: Val::Percent(0.),
+                            height: Val::Percent(100.),
+                            ..default()
+                        },
+                        BackgroundColor(Color::WHITE),
+                        CaptureBarFill,
+                    ));
+                });
+        });
+
+    // Spawn overlay for the main menu, lobby and post-match summary
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+            MatchOverlay,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load("fonts/OpenSans.ttf"),
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(JustifyText::Center),
+                MatchOverlayText,
+            ));
+        });
+
+    // Spawn scoreboard, hidden until Tab is held
+    let columns = scoreboard_columns();
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                display: Display::None,
+                ..default()
+            },
+            Scoreboard,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(520.),
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(6.),
+                        padding: UiRect::all(Val::Px(12.)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+                ))
+                .with_children(|panel| {
+                    panel
+                        .spawn(Node {
+                            display: Display::Grid,
+                            grid_template_columns: columns.clone(),
+                            ..default()
+                        })
+                        .with_children(|header| {
+                            for title in ["Player", "Score", "K", "D", "A", "Ping"] {
+                                header.spawn((
+                                    Text::new(title),
+                                    TextFont {
+                                        font: asset_server.load("fonts/OpenSans.ttf"),
+                                        font_size: 16.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::from(GRAY_400)),
+                                ));
+                            }
+                        });
+                    panel.spawn((
+                        Node {
+                            display: Display::Grid,
+                            grid_template_columns: columns,
+                            row_gap: Val::Px(4.),
+                            ..default()
+                        },
+                        ScoreboardRows,
+                    ));
+                });
+        });
+
+    // Spawn weapon bar, one slot per loadout slot
     commands
         .spawn(Node {
             position_type: PositionType::Absolute,
@@ -138,21 +476,48 @@ pub fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             left: Val::Px(10.),
             display: Display::Flex,
             flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(5.),
             ..default()
         })
         .with_children(|parent| {
+            for slot in WeaponSlotKind::ALL {
+                parent.spawn((
+                    Node {
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        width: Val::Px(50.),
+                        height: Val::Px(50.),
+                        border: UiRect::all(Val::Px(2.5)),
+                        ..Default::default()
+                    },
+                    BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.5).into()),
+                    BorderColor(Color::WHITE),
+                    WeaponSlot { slot }
+                ));
+            }
+
+            // ammo of the held ranged weapon, empty for other weapons
             parent.spawn((
                 Node {
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    width: Val::Px(50.),
-                    height: Val::Px(50.),
-                    border: UiRect::all(Val::Px(2.5)),
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::left(Val::Px(10.)),
                     ..Default::default()
                 },
-                BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.5).into()),
-                BorderColor(Color::from(BLUE_300)),
-                WeaponSlot
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load("fonts/OpenSans.ttf"),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                AmmoText,
             ));
         });
 }
+
+// HELPER FUNCTION
+
+/// Helper function for the columns of the scoreboard, a wide one for the name and narrow ones for the numbers
+fn scoreboard_columns() -> Vec<RepeatedGridTrack> {
+    vec![GridTrack::flex(3.0), RepeatedGridTrack::flex(5, 1.0)]
+}
@@ -4,7 +4,7 @@ use bevy::math::{Vec2, Vec3};
 use bevy::pbr::StandardMaterial;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
-use crate::components::world::NotGround;
+use crate::components::world::{NotGround, WORLD_GROUP};
 
 pub fn spawn_wall(
     commands: &mut Commands,
@@ -65,6 +65,7 @@ pub fn spawn_wall(
                 coefficient: 0.0,
                 combine_rule: CoefficientCombineRule::Min,
             },
+            CollisionGroups::new(WORLD_GROUP, Group::ALL),
             NotGround
         ));
     }
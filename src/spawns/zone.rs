@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+use crate::components::world::SafeZoneBoundary;
+use crate::resources::game::WorldAttribute;
+
+/**
+Spawns the see-through wall at the edge of the safe zone.
+It is a unit cylinder scaled to the zone, hidden until a match uses the zone.
+*/
+pub fn spawn_safe_zone_boundary(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    attributes: &Res<WorldAttribute>,
+) {
+    commands.spawn((
+        Transform::from_xyz(0.0, attributes.wall_height / 2.0, 0.0),
+        Mesh3d(meshes.add(Cylinder::new(1.0, 1.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(0.3, 0.6, 1.0, 0.25),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            // Seen from inside the zone as well as from outside
+            cull_mode: None,
+            double_sided: true,
+            ..default()
+        })),
+        Visibility::Hidden,
+        SafeZoneBoundary,
+    ));
+}
@@ -0,0 +1,138 @@
+use crate::components::combat::{AreaEffect, DamageSource, DamageType, Detonation, FieldKind, Fuse, Obscured, Slowed};
+use crate::components::player::Player;
+use crate::events::combat::DamageEvent;
+use crate::resources::item::ItemAssets;
+use crate::spawns::area::spawn_area_effect;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Seconds a status keeps applying after the player leaves the area that gave it
+const STATUS_LINGER: f32 = 0.3;
+
+/**
+Counts down fused throwables and sets them off.
+Explosions damage and push everything in range that isn't behind a structure,
+fields leave an [`AreaEffect`] where the throwable came to rest.
+*/
+pub fn fuse_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    item_assets: Res<ItemAssets>,
+    rapier_context: ReadDefaultRapierContext,
+    mut fuse_query: Query<(Entity, &Transform, &mut Fuse, &DamageSource)>,
+    mut body_query: Query<(Entity, &Transform, &RigidBody, Option<&mut ExternalImpulse>, Has<Player>), Without<Fuse>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (entity, transform, mut fuse, source) in fuse_query.iter_mut() {
+        if !fuse.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        let center = transform.translation;
+
+        match fuse.detonation {
+            Detonation::Explosion { radius, damage, knockback } => {
+                debug!("{} exploded at {}", source.cause, center);
+                for (target, target_transform, rigid_body, impulse, is_player) in body_query.iter_mut() {
+                    if *rigid_body != RigidBody::Dynamic {
+                        continue;
+                    }
+                    let offset = target_transform.translation - center;
+                    let distance = offset.length();
+                    if distance > radius || is_occluded(rapier_context.single(), center, target_transform.translation) {
+                        continue;
+                    }
+
+                    // Closer targets are hit harder
+                    let falloff = 1.0 - distance / radius;
+                    let push = (offset.normalize_or(Vec3::Y) + Vec3::Y * 0.5).normalize() * knockback * falloff;
+                    if is_player {
+                        damage_events.send(DamageEvent {
+                            target,
+                            amount: damage * falloff,
+                            damage_type: DamageType::Explosive,
+                            knockback: push,
+                            hit_stun: 0.0,
+                            origin: center,
+                            point: target_transform.translation,
+                            source: source.clone(),
+                        });
+                    } else if let Some(mut impulse) = impulse {
+                        impulse.impulse += push;
+                    } else {
+                        commands.entity(target).insert(ExternalImpulse {
+                            impulse: push,
+                            ..default()
+                        });
+                    }
+                }
+            }
+            Detonation::Field { kind, radius, duration } => {
+                debug!("{} released a {:?} field at {}", source.cause, kind, center);
+                spawn_area_effect(&mut commands, &item_assets, kind, radius, duration, center);
+            }
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Applies the status of each area effect to the players inside it and removes expired areas
+pub fn area_effect_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut area_query: Query<(Entity, &Transform, &mut AreaEffect)>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+) {
+    for (entity, transform, mut area) in area_query.iter_mut() {
+        if area.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        for (player, player_transform) in player_query.iter() {
+            if player_transform.translation.distance(transform.translation) > area.radius {
+                continue;
+            }
+            let timer = Timer::from_seconds(STATUS_LINGER, TimerMode::Once);
+            match area.kind {
+                FieldKind::Smoke => {
+                    commands.entity(player).insert(Obscured { timer });
+                }
+                FieldKind::Slow { factor } => {
+                    commands.entity(player).insert(Slowed { factor, timer });
+                }
+            }
+        }
+    }
+}
+
+/// Removes statuses from players once they run out
+pub fn status_effect_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut slowed_query: Query<(Entity, &mut Slowed)>,
+    mut obscured_query: Query<(Entity, &mut Obscured)>,
+) {
+    for (entity, mut slowed) in slowed_query.iter_mut() {
+        if slowed.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Slowed>();
+        }
+    }
+    for (entity, mut obscured) in obscured_query.iter_mut() {
+        if obscured.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Obscured>();
+        }
+    }
+}
+
+// HELPER FUNCTION
+
+/// Helper function to check if a structure, wall or the ground blocks the line between two points
+pub fn is_occluded(context: &RapierContext, from: Vec3, to: Vec3) -> bool {
+    let offset = to - from;
+    let distance = offset.length();
+    if distance <= f32::EPSILON {
+        return false;
+    }
+    let filter = QueryFilter::only_fixed().exclude_sensors();
+    context.cast_ray(from, offset / distance, distance, true, filter).is_some()
+}
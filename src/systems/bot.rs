@@ -0,0 +1,455 @@
+use crate::components::bot::*;
+use crate::components::camera::CameraSensitivity;
+use crate::components::combat::{Obscured, Respawning};
+use crate::components::objective::CaptureZone;
+use crate::components::player::*;
+use crate::components::team::Team;
+use crate::components::world::EntityName;
+use crate::resources::game::WorldAttribute;
+use crate::resources::launch::LaunchOptions;
+use crate::resources::navigation::NavGrid;
+use crate::resources::rng::GameRng;
+use crate::resources::rules::MatchRules;
+use crate::resources::zone::SafeZone;
+use crate::spawns::player::{spawn_bot, BOT_NAMES, PLAYER_SPAWN_POSITION};
+use crate::systems::area::is_occluded;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Height of the eyes above the player's center, matching the camera
+const EYE_HEIGHT: f32 = 1.5;
+/// Distance at which a melee swing reaches the target
+const MELEE_RANGE: f32 = 2.0;
+/// Farthest a bot throws from
+const THROW_RANGE: f32 = 15.0;
+/// Distance a bot with a ranged weapon tries to keep to its target
+const RANGED_DISTANCE: f32 = 10.0;
+/// Distance from its attacker at which a retreating bot stops to heal
+const SAFE_DISTANCE: f32 = 15.0;
+
+/**
+Lets every bot decide what to do next by scoring its options:
+retreating when hurt with an enemy in sight, engaging the closest visible enemy,
+collecting the closest useful item and roaming the arena.
+Allies and players hidden by smoke or walls aren't seen. A goal against a newly spotted enemy
+is kept pending until the bot's reaction time has passed, the bot keeps doing the best other thing until then.
+*/
+pub fn bot_think_system(
+    (time, world_attribute, safe_zone, nav_grid): (Res<Time>, Res<WorldAttribute>, Res<SafeZone>, Res<NavGrid>),
+    mut rng: ResMut<GameRng>,
+    rapier_context: ReadDefaultRapierContext,
+    mut bot_query: Query<
+        (Entity, &mut Bot, &Transform, &PlayerStats, &Inventory, Option<&Team>),
+        (With<Player>, Without<Respawning>),
+    >,
+    player_query: Query<(Entity, &Transform, &PlayerStats, Option<&Team>), (With<Player>, Without<Respawning>, Without<Obscured>)>,
+    item_query: Query<(Entity, &Item, &Transform), Without<PickupDelay>>,
+    hill_query: Query<&CaptureZone>,
+) {
+    let context = rapier_context.single();
+    let hill = hill_query.iter().find(|zone| zone.active).map(CaptureZone::center);
+
+    for (entity, mut bot, transform, player_stats, inventory, team) in bot_query.iter_mut() {
+        if !bot.think_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let profile = bot.difficulty.profile();
+        let position = transform.translation;
+        let eye = position + Vec3::Y * EYE_HEIGHT;
+
+        let visible_enemies: Vec<(Entity, Vec3, f32)> = player_query
+            .iter()
+            .filter(|(other, _, stats, other_team)| {
+                *other != entity && stats.health > 0.0 && !Team::are_allies(team, *other_team)
+            })
+            .map(|(other, other_transform, ..)| {
+                (other, other_transform.translation, other_transform.translation.distance(position))
+            })
+            .filter(|(_, other_position, distance)| {
+                *distance <= BOT_SIGHT_RANGE && !is_occluded(context, eye, *other_position)
+            })
+            .collect();
+
+        // Remember when each enemy in sight was first seen, forgetting those out of sight
+        let now = time.elapsed_secs();
+        bot.spotted.retain(|(spotted, _)| visible_enemies.iter().any(|(enemy, ..)| enemy == spotted));
+        for (enemy, ..) in &visible_enemies {
+            if !bot.spotted.iter().any(|(spotted, _)| spotted == enemy) {
+                bot.spotted.push((*enemy, now));
+            }
+        }
+        let enemy = visible_enemies.into_iter().min_by(|a, b| a.2.total_cmp(&b.2));
+
+        let item = item_query
+            .iter()
+            .filter(|(_, item, _)| is_useful_item(inventory, item))
+            .map(|(item_entity, item, item_transform)| {
+                (item_entity, item, item_transform.translation, item_transform.translation.distance(position))
+            })
+            .filter(|(.., distance)| *distance <= profile.item_awareness)
+            .min_by(|a, b| a.3.total_cmp(&b.3));
+
+        let health = player_stats.health / player_stats.max_health.max(1.0);
+        let armed = is_armed(inventory);
+
+        // Each option is scored, with the point the bot walks to for it, retreating walks away instead
+        let roam = roam_goal(&bot, position, hill, &safe_zone, &world_attribute, &mut rng);
+        let mut options = vec![(0.1, roam, roam_point(roam))];
+        if let Some((enemy, enemy_position, distance)) = enemy {
+            let retreat_health = profile.retreat_health();
+            if health < retreat_health {
+                options.push((1.0 + retreat_health - health, BotGoal::Retreat(enemy), None));
+            }
+            let closeness = 1.0 - distance / BOT_SIGHT_RANGE;
+            let score = if armed { 0.4 + 0.4 * profile.aggression + 0.4 * closeness } else { 0.2 * closeness };
+            options.push((score, BotGoal::Engage(enemy), Some(enemy_position)));
+        }
+        if let Some((item_entity, item, item_position, distance)) = item {
+            let weapon_bonus = if !armed && item.type_ == ItemType::Weapon { 0.4 } else { 0.0 };
+            let score = 0.5 * (1.0 - distance / profile.item_awareness) + weapon_bonus;
+            options.push((score, BotGoal::Collect(item_entity), Some(item_position)));
+        }
+
+        let error = rng.direction_in_cone(Vec3::NEG_Z, profile.aim_error);
+        bot.aim_offset = Quat::from_rotation_arc(Vec3::NEG_Z, error);
+
+        let path_to = |destination: Option<Vec3>| {
+            destination
+                .and_then(|destination| nav_grid.find_path(position, destination))
+                .unwrap_or_default()
+        };
+        let best = options.iter().copied().max_by(|a, b| a.0.total_cmp(&b.0));
+        let best_reacted = options
+            .iter()
+            .copied()
+            .filter(|(_, goal, _)| bot.has_reacted(goal, now))
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        bot.pending = best
+            .filter(|(_, goal, _)| !bot.has_reacted(goal, now))
+            .map(|(_, goal, destination)| (goal, path_to(destination)));
+        if let Some((_, goal, destination)) = best_reacted {
+            bot.goal = goal;
+            bot.path = path_to(destination);
+        }
+    }
+}
+
+/**
+Turns each bot's goal into intents for this frame, the same intents the local player's input produces.
+Bots pick the weapon that suits the distance, keep ranged weapons at a distance and close in to swing melee weapons.
+They turn no faster than their difficulty allows and only attack once they face their target.
+*/
+pub fn bot_act_system(
+    time: Res<Time>,
+    mut bot_query: Query<
+        (
+            &mut Bot,
+            &mut PlayerIntent,
+            &Transform,
+            &Direction,
+            &CameraSensitivity,
+            &Inventory,
+            &ItemCooldowns,
+            Has<ItemChannel>,
+        ),
+        (With<Player>, Without<Respawning>),
+    >,
+    target_query: Query<&Transform>,
+) {
+    // Largest angle between where a bot looks and its target at which it still attacks
+    const FACING_TOLERANCE: f32 = 0.25;
+
+    for (mut bot, mut intent, transform, direction, sensitivity, inventory, cooldowns, is_channeling) in
+        bot_query.iter_mut()
+    {
+        bot.attack_timer.tick(time.delta());
+        // A jump waits for the next movement tick like the local player's, it is only cleared once used
+        *intent = PlayerIntent { jump: intent.jump, ..default() };
+        let position = transform.translation;
+
+        // Reactions are checked every frame, not only when the bot thinks
+        if bot.pending.as_ref().is_some_and(|(goal, _)| bot.has_reacted(goal, time.elapsed_secs())) {
+            if let Some((goal, path)) = bot.pending.take() {
+                bot.goal = goal;
+                bot.path = path;
+            }
+        }
+
+        let target = match bot.goal {
+            BotGoal::Roam(point) => Some(point),
+            BotGoal::Collect(entity) | BotGoal::Engage(entity) | BotGoal::Retreat(entity) => {
+                target_query.get(entity).ok().map(|target| target.translation)
+            }
+        };
+        let Some(target) = target else {
+            // The target is gone, wait for the next decision
+            bot.goal = BotGoal::Roam(position);
+            continue;
+        };
+        let distance = position.distance(target);
+        let toward = flat_direction(position, target);
+        // Walk along the path around walls and onto structures, straight at the target once it runs out
+        let steer_point = next_waypoint(&mut bot.path, position).unwrap_or(target);
+        let approach = flat_direction(position, steer_point);
+
+        match bot.goal {
+            BotGoal::Roam(_) | BotGoal::Collect(_) => {
+                intent.movement = approach;
+                intent.look = Some(approach).filter(|look| *look != Vec3::ZERO);
+            }
+            BotGoal::Retreat(_) => {
+                intent.look = Some(target - (position + Vec3::Y * EYE_HEIGHT));
+                let heal_slot = inventory.slots.iter().position(|slot| {
+                    slot.as_ref().is_some_and(|item| matches!(item.effect, ItemEffect::Heal(_)))
+                });
+                if distance < SAFE_DISTANCE || is_channeling {
+                    // Standing still while channeling, moving would interrupt the heal
+                    intent.movement = if is_channeling { Vec3::ZERO } else { -toward };
+                    intent.block = true;
+                } else if let Some(slot) = heal_slot {
+                    if slot != inventory.current_selected_item {
+                        intent.cycle_item = 1;
+                    } else if inventory.selected_item().is_some_and(|item| cooldowns.is_ready(item)) {
+                        intent.use_item = true;
+                    }
+                }
+            }
+            BotGoal::Engage(_) => {
+                let aim = bot.aim_offset * (target - (position + Vec3::Y * EYE_HEIGHT));
+                intent.look = Some(aim);
+
+                let wanted = preferred_weapon_slot(inventory, distance);
+                if let Some(slot) = wanted.filter(|slot| *slot != inventory.weapons.active) {
+                    intent.switch_weapon = Some(slot);
+                } else {
+                    let ready = bot.attack_timer.finished();
+                    match inventory.weapons.active_weapon() {
+                        Some(weapon) if weapon.ranged.is_some() => {
+                            intent.movement = if distance > RANGED_DISTANCE * 1.2 {
+                                approach
+                            } else if distance < RANGED_DISTANCE * 0.6 {
+                                -toward
+                            } else {
+                                Vec3::ZERO
+                            };
+                            intent.fire = true;
+                        }
+                        Some(weapon) if weapon.throwable && distance > MELEE_RANGE * 2.0 && distance < THROW_RANGE => {
+                            intent.movement = approach;
+                            intent.throw = ready;
+                        }
+                        Some(_) => {
+                            intent.movement = if distance > MELEE_RANGE * 0.8 { approach } else { Vec3::ZERO };
+                            intent.attack = ready && distance <= MELEE_RANGE;
+                        }
+                        None => {
+                            // Without a weapon, throw whatever throwable items are in the inventory
+                            intent.movement = if distance > THROW_RANGE * 0.8 { approach } else { Vec3::ZERO };
+                            let throw_slot = inventory.slots.iter().position(|slot| {
+                                slot.as_ref().is_some_and(|item| matches!(item.effect, ItemEffect::Throw { .. }))
+                            });
+                            match throw_slot {
+                                Some(slot) if slot != inventory.current_selected_item => intent.cycle_item = 1,
+                                Some(_) => {
+                                    intent.use_item = ready
+                                        && distance < THROW_RANGE
+                                        && inventory.selected_item().is_some_and(|item| cooldowns.is_ready(item));
+                                }
+                                None => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(look) = intent.look {
+            let max_turn = sensitivity.x * bot.difficulty.profile().turn_speed * time.delta_secs();
+            let turned = turn_towards(direction.direction, look, max_turn);
+            let facing = turned.angle_between(look) <= FACING_TOLERANCE;
+            if matches!(bot.goal, BotGoal::Engage(_)) && !facing {
+                intent.attack = false;
+                intent.fire = false;
+                intent.throw = false;
+                intent.use_item = false;
+            }
+            intent.look = Some(turned);
+        }
+        if intent.attack || intent.throw || intent.use_item {
+            bot.attack_timer.reset();
+        }
+        // Jump onto structures the path climbs
+        intent.jump |= intent.movement != Vec3::ZERO && intent.movement == approach && needs_climb(position, steer_point);
+    }
+}
+
+/**
+Fills the empty player slots of the match rules with bots, removing bots when there are more than slots left.
+Bots already in the game switch to the difficulty of the rules. `--bots` on launch asks for a fixed number of bots instead.
+*/
+pub fn fill_bot_slots_system(
+    mut commands: Commands,
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+    (rules, options): (Res<MatchRules>, Res<LaunchOptions>),
+    human_query: Query<(), (With<Player>, Without<Bot>)>,
+    mut bot_query: Query<(Entity, &EntityName, &mut Bot)>,
+) {
+    let wanted = options
+        .bots
+        .unwrap_or_else(|| rules.bot_slots.saturating_sub(human_query.iter().count()));
+
+    let mut names = Vec::new();
+    for (index, (bot, name, mut bot_state)) in bot_query.iter_mut().enumerate() {
+        if index >= wanted {
+            commands.entity(bot).despawn_recursive();
+            continue;
+        }
+        bot_state.difficulty = rules.bot_difficulty;
+        names.push(name.name().to_string());
+    }
+
+    for index in names.len()..wanted {
+        let name = BOT_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain((1..).map(|number| format!("Bot {}", number)))
+            .find(|name| !names.contains(name))
+            .unwrap_or_default();
+        // Spread out around the spawn, the arena reset puts everyone on spawn points when the warmup starts
+        let angle = std::f32::consts::TAU * index as f32 / wanted as f32;
+        let position = PLAYER_SPAWN_POSITION + Vec3::new(angle.cos(), 0.0, angle.sin()) * 5.0;
+        spawn_bot(&mut commands, &mut meshes, &mut materials, &rules, &name, position);
+        names.push(name);
+    }
+}
+
+// HELPER FUNCTION
+
+/// Helper function to drop the path points a bot has reached and give the one it heads for next
+fn next_waypoint(path: &mut Vec<Vec3>, position: Vec3) -> Option<Vec3> {
+    // How close a bot has to get to a point before it moves on to the next
+    const REACHED: f32 = 0.75;
+
+    let reached = path
+        .iter()
+        .take_while(|point| point.xz().distance(position.xz()) < REACHED)
+        .count();
+    path.drain(..reached);
+    path.first().copied()
+}
+
+/// Helper function to check if a bot has to jump to get onto the surface at `point`
+fn needs_climb(position: Vec3, point: Vec3) -> bool {
+    // The bot's center is half its height above the surface it stands on
+    point.y > position.y + 0.5 && point.xz().distance(position.xz()) < 3.0
+}
+
+/// Helper function to find the point a roaming goal heads for
+fn roam_point(goal: BotGoal) -> Option<Vec3> {
+    match goal {
+        BotGoal::Roam(point) => Some(point),
+        _ => None,
+    }
+}
+
+/// Helper function to turn a direction towards another by at most `max_angle` radians
+fn turn_towards(current: Vec3, desired: Vec3, max_angle: f32) -> Vec3 {
+    let (Some(current), Some(desired)) = (current.try_normalize(), desired.try_normalize()) else {
+        return desired;
+    };
+    let angle = current.angle_between(desired);
+    if angle <= max_angle {
+        return desired;
+    }
+    Quat::IDENTITY.slerp(Quat::from_rotation_arc(current, desired), max_angle / angle) * current
+}
+
+/// Helper function to find the direction from one point to another along the ground
+fn flat_direction(from: Vec3, to: Vec3) -> Vec3 {
+    (to - from).with_y(0.0).normalize_or_zero()
+}
+
+/// Helper function to check if a bot has anything to fight with
+fn is_armed(inventory: &Inventory) -> bool {
+    WeaponSlotKind::ALL.iter().any(|slot| inventory.weapons.get(*slot).is_some())
+        || inventory
+            .slots
+            .iter()
+            .flatten()
+            .any(|item| matches!(item.effect, ItemEffect::Throw { .. }))
+}
+
+/// Helper function to check if picking up an item would help a bot
+fn is_useful_item(inventory: &Inventory, item: &Item) -> bool {
+    match &item.effect {
+        ItemEffect::WeaponItem(weapon) => inventory.weapons.get(weapon.slot).is_none(),
+        ItemEffect::Ammo { ammo_type, .. } => WeaponSlotKind::ALL.iter().any(|slot| {
+            inventory
+                .weapons
+                .get(*slot)
+                .and_then(|weapon| weapon.ranged.as_ref())
+                .is_some_and(|ranged| ranged.ammo_type == *ammo_type)
+        }),
+        _ => inventory.has_space(),
+    }
+}
+
+/**
+Helper function to pick the weapon slot that suits the distance to the target:
+a ranged weapon with ammo when the target is out of reach, otherwise a melee weapon.
+`None` when the bot has no weapon at all.
+*/
+fn preferred_weapon_slot(inventory: &Inventory, distance: f32) -> Option<WeaponSlotKind> {
+    let ranged = WeaponSlotKind::ALL.into_iter().find(|slot| {
+        inventory
+            .weapons
+            .get(*slot)
+            .and_then(|weapon| weapon.ranged.as_ref())
+            .is_some_and(|ranged| ranged.magazine > 0 || ranged.reserve_ammo > 0)
+    });
+    let melee = WeaponSlotKind::ALL.into_iter().find(|slot| {
+        inventory.weapons.get(*slot).is_some_and(|weapon| weapon.ranged.is_none())
+    });
+    if distance > MELEE_RANGE * 2.0 {
+        ranged.or(melee)
+    } else {
+        melee.or(ranged)
+    }
+}
+
+/**
+Helper function to pick where a roaming bot heads.
+Bots head for the hill when there is one and stay inside the safe zone,
+otherwise they keep walking to a random point and pick a new one once they get there.
+*/
+fn roam_goal(
+    bot: &Bot,
+    position: Vec3,
+    hill: Option<Vec3>,
+    safe_zone: &SafeZone,
+    world_attribute: &WorldAttribute,
+    rng: &mut GameRng,
+) -> BotGoal {
+    // Keeps roam points away from the walls
+    const MARGIN: f32 = 5.0;
+
+    if let Some(hill) = hill {
+        return BotGoal::Roam(hill);
+    }
+    if let BotGoal::Roam(point) = bot.goal {
+        let arrived = flat_direction(position, point) == Vec3::ZERO || position.xz().distance(point.xz()) < 2.0;
+        if !arrived && (!safe_zone.active || safe_zone.contains(point)) {
+            return bot.goal;
+        }
+    }
+
+    let point = if safe_zone.active {
+        let angle = rng.range(0.0, std::f32::consts::TAU);
+        let distance = safe_zone.radius * 0.8 * rng.next_f32().sqrt();
+        safe_zone.center + Vec2::from_angle(angle) * distance
+    } else {
+        let bounds = Vec2::new(world_attribute.width, world_attribute.height) / 2.0 - MARGIN;
+        Vec2::new(rng.range(-bounds.x, bounds.x), rng.range(-bounds.y, bounds.y))
+    };
+    BotGoal::Roam(Vec3::new(point.x, 0.0, point.y))
+}
@@ -22,7 +22,7 @@ pub fn toggle_camera_mode_system(
 // Unified camera system
 pub fn camera_system(
     camera_state: ResMut<CameraState>,
-    mut player_query: Query<(&mut Transform, &CameraSensitivity, &mut Direction), With<Player>>,
+    mut player_query: Query<(&mut Transform, &CameraSensitivity, &mut Direction), With<LocalPlayer>>,
     mut camera_query: Query<(&mut Transform, &GlobalTransform), (With<PlayerCamera>, Without<Player>)>,
     mouse_input: Res<ButtonInput<MouseButton>>,
     accumulated_mouse_motion: Res<AccumulatedMouseMotion>,
@@ -33,12 +33,12 @@ pub fn camera_system(
         return;
     }
 
-    // Process horizontal rotation for the player (yaw)
-    let delta_yaw = -delta.x * player_query.single().1.x; // using the sensitivity.x
     let (mut player_transform, camera_sensitivity, mut direction) = match player_query.get_single_mut() {
         Ok(data) => data,
         Err(_) => return,
     };
+    // Process horizontal rotation for the player (yaw)
+    let delta_yaw = -delta.x * camera_sensitivity.x; // using the sensitivity.x
 
     let (mut current_yaw, _, _) = player_transform.rotation.to_euler(EulerRot::YXZ);
     current_yaw += delta_yaw;
@@ -0,0 +1,297 @@
+use crate::components::combat::{Blocking, DamageSource, InCombat, MeleeSwing, SpawnProtection, Staggered, Stunned};
+use crate::components::player::{Direction, Inventory, Player, PlayerStats};
+use crate::components::team::Team;
+use crate::components::world::EntityName;
+use crate::events::combat::{DamageEvent, DamageTakenEvent, DeathEvent};
+use crate::events::notification::{NotificationEvent, NotificationPriority};
+use crate::resources::game::DamageRules;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Fraction of the damage and knockback a block stops
+const BLOCK_REDUCTION: f32 = 0.75;
+/// Hits within about 60 degrees of where the player faces count as frontal
+const BLOCK_MIN_FACING: f32 = 0.5;
+
+/// Everything about a player that taking a hit reads or changes
+type DamageTarget = (
+    &'static mut PlayerStats,
+    &'static mut ExternalImpulse,
+    &'static mut Inventory,
+    &'static EntityName,
+    &'static Transform,
+    &'static Direction,
+    Option<&'static Blocking>,
+    Option<&'static Staggered>,
+    Option<&'static Stunned>,
+    Has<SpawnProtection>,
+    Option<&'static Team>,
+);
+
+/**
+Applies damage events to the health of players, sending a death event for the final blow.
+Knockback is added to the player's impulse and staggers them so movement doesn't cancel it.
+Blocking players take less damage from the front, and parry it entirely if they just raised the block.
+*/
+pub fn apply_damage_system(
+    mut commands: Commands,
+    rules: Res<DamageRules>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut taken_events: EventWriter<DamageTakenEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+    mut notification_events: EventWriter<NotificationEvent>,
+    mut player_query: Query<DamageTarget, With<Player>>,
+) {
+    // Resistance can never make a player fully immune
+    const MAX_RESISTANCE: f32 = 0.9;
+    // Seconds a knocked back player keeps their momentum
+    const STAGGER_TIME: f32 = 0.35;
+    // Seconds after a hit before armor starts to regenerate
+    const COMBAT_TIME: f32 = 4.0;
+
+    for event in damage_events.read() {
+        if !rules.self_damage && event.source.is_from(event.target) {
+            continue;
+        }
+        if !rules.friendly_fire && is_friendly_fire(&player_query, event) {
+            continue;
+        }
+        let Ok((
+            mut player_stats,
+            mut impulse,
+            mut inventory,
+            name,
+            transform,
+            direction,
+            blocking,
+            staggered,
+            stunned,
+            is_protected,
+            _,
+        )) = player_query.get_mut(event.target)
+        else {
+            continue;
+        };
+        // Already dead, nobody gets credited twice
+        if player_stats.health <= 0.0 || is_protected {
+            continue;
+        }
+
+        let mut amount = event.amount;
+        let mut knockback = event.knockback;
+        let mut hit_stun = event.hit_stun;
+        let mut parried = false;
+        if let Some(blocking) = blocking.filter(|_| is_frontal(transform, direction, event.origin)) {
+            if let Some(weapon) = inventory.weapons.active_weapon_mut() {
+                weapon.decrement_durability();
+            }
+            parried = blocking.is_parrying();
+            let kept = if parried { 0.0 } else { 1.0 - BLOCK_REDUCTION };
+            amount *= kept;
+            knockback *= kept;
+            hit_stun = 0.0;
+            notification_events.send(if parried {
+                NotificationEvent::new(event.target, "Parried!", NotificationPriority::Normal)
+            } else {
+                NotificationEvent::new(event.target, "Blocked", NotificationPriority::Low)
+            });
+        }
+
+        let resistance = player_stats.damage_resistance.clamp(0.0, MAX_RESISTANCE);
+        let mut damage = amount * (1.0 - resistance);
+        if !event.damage_type.bypasses_armor() {
+            damage = player_stats.absorb_with_armor(damage);
+        }
+        player_stats.health = (player_stats.health - damage).max(0.0);
+        // A fully parried hit doesn't stop armor from regenerating
+        if damage > 0.0 {
+            commands.entity(event.target).insert(InCombat {
+                timer: Timer::from_seconds(COMBAT_TIME, TimerMode::Once),
+            });
+        }
+        impulse.impulse += knockback;
+
+        if knockback != Vec3::ZERO || hit_stun > 0.0 {
+            let duration = STAGGER_TIME.max(hit_stun);
+            // A longer stagger already in progress wins
+            if staggered.is_none_or(|staggered| staggered.timer.remaining_secs() < duration) {
+                commands.entity(event.target).insert(Staggered {
+                    timer: Timer::from_seconds(duration, TimerMode::Once),
+                });
+            }
+        }
+        if hit_stun > 0.0 && stunned.is_none_or(|stunned| stunned.timer.remaining_secs() < hit_stun) {
+            commands.entity(event.target).insert(Stunned {
+                timer: Timer::from_seconds(hit_stun, TimerMode::Once),
+            });
+        }
+        debug!(
+            "Player {:?} took {:.1} {:?} damage from {}, health is now {:.1}, armor {:.1}",
+            name, damage, event.damage_type, event.source.cause, player_stats.health, player_stats.armor
+        );
+
+        let lethal = player_stats.health <= 0.0;
+        taken_events.send(DamageTakenEvent {
+            target: event.target,
+            amount: damage,
+            origin: event.origin,
+            point: event.point,
+            source: event.source.clone(),
+            lethal,
+        });
+        if lethal {
+            death_events.send(DeathEvent {
+                victim: event.target,
+                victim_name: name.name().to_string(),
+                source: event.source.clone(),
+            });
+        }
+
+        if parried {
+            let defender_position = transform.translation;
+            if let Some(attacker) = event.source.instigator.as_ref() {
+                punish_parried_attacker(&mut commands, &mut player_query, attacker.entity, defender_position);
+            }
+        }
+    }
+}
+
+/// Ends staggers and stuns once they run out
+pub fn stagger_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut staggered_query: Query<(Entity, &mut Staggered)>,
+    mut stunned_query: Query<(Entity, &mut Stunned)>,
+) {
+    for (entity, mut staggered) in staggered_query.iter_mut() {
+        if staggered.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Staggered>();
+        }
+    }
+    for (entity, mut stunned) in stunned_query.iter_mut() {
+        if stunned.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Stunned>();
+        }
+    }
+}
+
+/// Regenerates armor of players who have been out of combat long enough
+pub fn armor_regen_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_query: Query<(Entity, &mut PlayerStats, Option<&mut InCombat>), With<Player>>,
+) {
+    for (entity, mut player_stats, in_combat) in player_query.iter_mut() {
+        if let Some(mut in_combat) = in_combat {
+            if in_combat.timer.tick(time.delta()).finished() {
+                commands.entity(entity).remove::<InCombat>();
+            }
+            continue;
+        }
+        if player_stats.armor_regen <= 0.0 || player_stats.armor >= player_stats.max_armor {
+            continue;
+        }
+        let regen = player_stats.armor_regen * time.delta_secs();
+        player_stats.armor = (player_stats.armor + regen).min(player_stats.max_armor);
+    }
+}
+
+/// Damages players caught in melee swings and removes swings once they are over
+pub fn melee_swing_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: ReadDefaultRapierContext,
+    mut swing_query: Query<(Entity, &Transform, &mut MeleeSwing, &DamageSource)>,
+    player_query: Query<&Transform, (With<Player>, Without<MeleeSwing>)>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (entity, transform, mut swing, source) in swing_query.iter_mut() {
+        let mut touching = Vec::new();
+        rapier_context.single().intersections_with_shape(
+            transform.translation,
+            Quat::IDENTITY,
+            &Collider::ball(swing.radius),
+            QueryFilter::default().exclude_sensors(),
+            |hit| {
+                touching.push(hit);
+                true
+            },
+        );
+
+        for hit in touching {
+            let Ok(hit_transform) = player_query.get(hit) else {
+                continue;
+            };
+            if source.is_from(hit) || swing.hits.contains(&hit) {
+                continue;
+            }
+            swing.hits.push(hit);
+            damage_events.send(DamageEvent {
+                target: hit,
+                amount: swing.damage,
+                damage_type: swing.damage_type,
+                knockback: swing.knockback,
+                hit_stun: swing.hit_stun,
+                origin: swing.origin,
+                point: hit_transform.translation,
+                source: source.clone(),
+            });
+        }
+
+        if swing.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// HELPER FUNCTION
+
+/// Helper function to check if a hit coming from `origin` is in front of the player
+fn is_frontal(transform: &Transform, direction: &Direction, origin: Vec3) -> bool {
+    let facing = direction.direction.with_y(0.0).normalize_or_zero();
+    let to_origin = (origin - transform.translation).with_y(0.0).normalize_or_zero();
+    facing.dot(to_origin) >= BLOCK_MIN_FACING
+}
+
+/// Helper function to check if damage comes from a teammate of the target, hurting yourself doesn't count
+fn is_friendly_fire(player_query: &Query<DamageTarget, With<Player>>, event: &DamageEvent) -> bool {
+    let Some(attacker) = event.source.instigator.as_ref().map(|instigator| instigator.entity) else {
+        return false;
+    };
+    if attacker == event.target {
+        return false;
+    }
+    let team_of = |entity: Entity| player_query.get(entity).ok().and_then(|(.., team)| team);
+    Team::are_allies(team_of(attacker), team_of(event.target))
+}
+
+/// Helper function to stagger and push back an attacker whose hit was parried
+fn punish_parried_attacker(
+    commands: &mut Commands,
+    player_query: &mut Query<DamageTarget, With<Player>>,
+    attacker: Entity,
+    defender_position: Vec3,
+) {
+    // How close the attacker must be for the parry to reach them
+    const PARRY_REACH: f32 = 3.0;
+    const PARRY_STUN: f32 = 0.6;
+    const PARRY_PUSH: f32 = 5.0;
+
+    let Ok((_, mut impulse, _, _, transform, ..)) = player_query.get_mut(attacker) else {
+        return;
+    };
+    let offset = transform.translation - defender_position;
+    if offset.length() > PARRY_REACH {
+        return;
+    }
+    commands.send_event(NotificationEvent::new(attacker, "Your attack was parried!", NotificationPriority::Normal));
+    impulse.impulse += (offset.with_y(0.0).normalize_or_zero() + Vec3::Y * 0.3) * PARRY_PUSH;
+    commands.entity(attacker).insert((
+        Staggered {
+            timer: Timer::from_seconds(PARRY_STUN, TimerMode::Once),
+        },
+        Stunned {
+            timer: Timer::from_seconds(PARRY_STUN, TimerMode::Once),
+        },
+    ));
+}
@@ -0,0 +1,278 @@
+use crate::components::combat::{AreaEffect, Eliminated, MeleeSwing, SpawnProtectionBubble};
+use crate::components::player::{Item, Player};
+use crate::components::score::Score;
+use crate::components::team::Team;
+use crate::components::world::{EntityName, SpawnPoint, ThrewObject};
+use crate::resources::game::{DamageRules, GameMode, GameState, MatchClock, MatchSettings};
+use crate::resources::item::ItemAssets;
+use crate::resources::launch::LaunchOptions;
+use crate::events::notification::{NotificationEvent, NotificationPriority};
+use crate::resources::rng::GameRng;
+use crate::resources::rules::{MatchRules, MATCH_RULES_PATH};
+use crate::spawns::player::respawn_player;
+use crate::systems::spawn::choose_spawn_point;
+use crate::systems::team::team_totals;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Run condition for systems that only run while players can move and fight
+pub fn gameplay_running(state: Res<State<GameState>>) -> bool {
+    state.get().is_playing()
+}
+
+/// Goes to the lobby when Enter is pressed in the main menu, right away when running headless
+pub fn main_menu_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    options: Res<LaunchOptions>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Enter) || options.headless {
+        next_state.set(GameState::Lobby);
+    }
+}
+
+/**
+Starts the warmup when Enter is pressed and enough players are in the lobby, without waiting when running headless.
+M switches the game mode and F toggles friendly fire, reloading the match rules resets both.
+*/
+pub fn lobby_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<MatchSettings>,
+    mut rules: ResMut<DamageRules>,
+    options: Res<LaunchOptions>,
+    player_query: Query<(), With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        settings.mode = match settings.mode {
+            GameMode::Deathmatch => GameMode::TeamDeathmatch,
+            GameMode::TeamDeathmatch => GameMode::LastStanding,
+            GameMode::LastStanding => GameMode::KingOfTheHill,
+            GameMode::KingOfTheHill => GameMode::Deathmatch,
+        };
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyF) {
+        rules.friendly_fire = !rules.friendly_fire;
+    }
+    let start = keyboard_input.just_pressed(KeyCode::Enter) || options.headless;
+    if start && player_query.iter().count() >= settings.min_players {
+        next_state.set(GameState::Warmup);
+    }
+}
+
+pub fn start_warmup_system(settings: Res<MatchSettings>, mut clock: ResMut<MatchClock>) {
+    clock.timer = Timer::from_seconds(settings.warmup_time, TimerMode::Once);
+}
+
+pub fn start_match_system(settings: Res<MatchSettings>, mut clock: ResMut<MatchClock>) {
+    clock.timer = Timer::from_seconds(settings.time_limit, TimerMode::Once);
+    clock.winner = None;
+}
+
+/// Shows the summary of the match that just ended, or quits once the matches asked for on launch are played
+pub fn start_post_match_system(
+    settings: Res<MatchSettings>,
+    options: Res<LaunchOptions>,
+    mut clock: ResMut<MatchClock>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    clock.timer = Timer::from_seconds(settings.post_match_time, TimerMode::Once);
+    clock.played += 1;
+    if options.headless {
+        info!(
+            "Match {} over, winner: {}",
+            clock.played,
+            clock.winner.as_deref().unwrap_or("nobody")
+        );
+    }
+    if options.matches.is_some_and(|matches| clock.played >= matches) {
+        exit_events.send(AppExit::Success);
+    }
+}
+
+/// Starts the match once the warmup is over
+pub fn warmup_system(
+    time: Res<Time>,
+    mut clock: ResMut<MatchClock>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if clock.timer.tick(time.delta()).just_finished() {
+        next_state.set(GameState::InProgress);
+    }
+}
+
+/**
+Ends the match when time runs out or a player reaches the frag limit, deciding the winner.
+In team modes the kills of the whole team count towards the limit and the team with the most kills wins.
+Last player standing ends when at most one player is left instead, with the survivor as the winner,
+and king of the hill when a player has held the hill long enough.
+*/
+pub fn match_end_system(
+    time: Res<Time>,
+    settings: Res<MatchSettings>,
+    mut clock: ResMut<MatchClock>,
+    score_query: Query<(&EntityName, &Score, Option<&Team>, Has<Eliminated>), With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let out_of_time = clock.timer.tick(time.delta()).just_finished();
+
+    if settings.mode == GameMode::LastStanding {
+        let survivors: Vec<_> = score_query
+            .iter()
+            .filter(|(.., eliminated)| !eliminated)
+            .map(|(name, score, ..)| (name, score))
+            .collect();
+        // Someone playing alone keeps going until the zone gets them
+        let decided = survivors.is_empty() || (survivors.len() == 1 && score_query.iter().count() > 1);
+        if !out_of_time && !decided {
+            return;
+        }
+        clock.winner = unique_leader(&survivors, Score::points);
+        next_state.set(GameState::PostMatch);
+        return;
+    }
+
+    if settings.mode.has_teams() {
+        let [red, blue] = team_totals(score_query.iter().map(|(_, score, team, _)| (team, score)));
+        if !out_of_time && red.kills.max(blue.kills) < settings.frag_limit {
+            return;
+        }
+        clock.winner = match red.kills.cmp(&blue.kills) {
+            std::cmp::Ordering::Greater => Some(format!("{} team", Team::Red.name())),
+            std::cmp::Ordering::Less => Some(format!("{} team", Team::Blue.name())),
+            std::cmp::Ordering::Equal => None,
+        };
+        next_state.set(GameState::PostMatch);
+        return;
+    }
+
+    if settings.mode == GameMode::KingOfTheHill {
+        let hold_limit_reached = score_query.iter().any(|(_, score, ..)| score.objective >= settings.hold_limit);
+        if !out_of_time && !hold_limit_reached {
+            return;
+        }
+        let players: Vec<_> = score_query.iter().map(|(name, score, ..)| (name, score)).collect();
+        clock.winner = unique_leader(&players, |score| score.objective);
+        next_state.set(GameState::PostMatch);
+        return;
+    }
+
+    let frag_limit_reached = score_query.iter().any(|(_, score, ..)| score.kills >= settings.frag_limit);
+    if !out_of_time && !frag_limit_reached {
+        return;
+    }
+
+    let players: Vec<_> = score_query.iter().map(|(name, score, ..)| (name, score)).collect();
+    // Whoever reached the frag limit wins it, points only decide matches that ran out of time
+    clock.winner = if frag_limit_reached {
+        unique_leader(&players, |score| score.kills)
+    } else {
+        unique_leader(&players, Score::points)
+    };
+    next_state.set(GameState::PostMatch);
+}
+
+/// Goes back to warmup for the next match once the summary has been shown long enough
+pub fn post_match_system(
+    time: Res<Time>,
+    mut clock: ResMut<MatchClock>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if clock.timer.tick(time.delta()).just_finished() {
+        next_state.set(GameState::Warmup);
+    }
+}
+
+/// Applies the item tuning of the match rules to the items just placed in the arena
+pub fn tune_items_system(rules: Res<MatchRules>, mut item_query: Query<&mut Item>) {
+    for mut item in item_query.iter_mut() {
+        rules.tune_item(&mut item);
+    }
+}
+
+/// Sets up the first match with the match rules loaded at startup
+pub fn apply_match_rules_system(
+    rules: Res<MatchRules>,
+    mut settings: ResMut<MatchSettings>,
+    mut damage_rules: ResMut<DamageRules>,
+) {
+    rules.apply_to(&mut settings, &mut damage_rules);
+}
+
+/**
+Reloads the match rules from their file when F5 is pressed between matches.
+A broken file keeps the rules that were loaded before.
+*/
+pub fn reload_match_rules_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut rules: ResMut<MatchRules>,
+    mut settings: ResMut<MatchSettings>,
+    mut damage_rules: ResMut<DamageRules>,
+    player_query: Query<Entity, With<Player>>,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F5) {
+        return;
+    }
+    let (message, priority) = match MatchRules::load(MATCH_RULES_PATH) {
+        Ok(loaded) => {
+            *rules = loaded;
+            rules.apply_to(&mut settings, &mut damage_rules);
+            (String::from("Match rules reloaded"), NotificationPriority::Normal)
+        }
+        Err(error) => {
+            error!("Failed to reload the match rules, {}", error);
+            (String::from("Failed to reload the match rules"), NotificationPriority::High)
+        }
+    };
+    for player in player_query.iter() {
+        notification_events.send(NotificationEvent::new(player, message.clone(), priority));
+    }
+}
+
+/**
+Clears the arena of pickups and everything thrown or left behind,
+and respawns every player with fresh stats, an empty inventory and no score.
+*/
+pub fn reset_arena_system(
+    mut commands: Commands,
+    (item_assets, rules): (Res<ItemAssets>, Res<MatchRules>),
+    mut rng: ResMut<GameRng>,
+    rapier_context: ReadDefaultRapierContext,
+    leftover_query: Query<
+        Entity,
+        Or<(With<Item>, With<ThrewObject>, With<AreaEffect>, With<MeleeSwing>, With<SpawnProtectionBubble>)>,
+    >,
+    spawn_point_query: Query<(&Transform, &SpawnPoint)>,
+    player_query: Query<(Entity, Option<&Team>), With<Player>>,
+) {
+    for entity in leftover_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let spawn_points: Vec<(Transform, Option<Team>)> = spawn_point_query
+        .iter()
+        .map(|(transform, spawn_point)| (*transform, spawn_point.team))
+        .collect();
+    let mut placed = Vec::new();
+    for (player, team) in player_query.iter() {
+        // Spread players out by treating the enemies already placed as threats
+        let team = team.copied();
+        let spawn = choose_spawn_point(rapier_context.single(), &mut rng, &spawn_points, team, &placed);
+        respawn_player(&mut commands, &item_assets, &rules, player, spawn);
+        commands.entity(player).insert(Score::default());
+        placed.push((spawn.translation, team));
+    }
+}
+
+// HELPER FUNCTION
+
+/// Helper function to find the name of the player ranked highest by `rank`, nobody wins a tie
+fn unique_leader(players: &[(&EntityName, &Score)], rank: impl Fn(&Score) -> u32) -> Option<String> {
+    let best = players.iter().map(|(_, score)| rank(score)).max()?;
+    let mut leaders = players.iter().filter(|(_, score)| rank(score) == best);
+    match (leaders.next(), leaders.next()) {
+        (Some((name, _)), None) => Some(name.name().to_string()),
+        _ => None,
+    }
+}
@@ -1,4 +1,17 @@
 pub mod player;
 pub mod camera;
 pub mod ui;
-pub mod window;
\ No newline at end of file
+pub mod window;
+pub mod combat;
+pub mod weapon;
+pub mod projectile;
+pub mod area;
+pub mod score;
+pub mod game;
+pub mod spawn;
+pub mod team;
+pub mod zone;
+pub mod objective;
+pub mod bot;
+pub mod navigation;
+pub mod prediction;
\ No newline at end of file
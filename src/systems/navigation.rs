@@ -0,0 +1,106 @@
+use crate::components::bot::Bot;
+use crate::components::world::{Ground, NotGround, Structure};
+use crate::resources::navigation::{NavCell, NavDebug, NavGrid, NavObstacle, NAV_CELL_SIZE};
+use bevy::color::palettes::css::{LIME, YELLOW};
+use bevy::color::palettes::tailwind::CYAN_400;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::f32::consts::FRAC_PI_2;
+
+/**
+Rebuilds the navigation grid from the ground, structures and walls whenever one of them is added, moved or removed,
+which also builds it once the level is loaded.
+The grid covers everything the static world spans.
+*/
+pub fn update_nav_grid_system(
+    mut nav_grid: ResMut<NavGrid>,
+    obstacle_query: Query<(&Transform, &Collider, Has<NotGround>), Or<(With<Ground>, With<Structure>, With<NotGround>)>>,
+    changed_query: Query<
+        (),
+        (Or<(With<Ground>, With<Structure>, With<NotGround>)>, Or<(Added<Collider>, Changed<Transform>)>),
+    >,
+    mut removed_ground: RemovedComponents<Ground>,
+    mut removed_structures: RemovedComponents<Structure>,
+    mut removed_walls: RemovedComponents<NotGround>,
+) {
+    let removed = removed_ground.read().count() + removed_structures.read().count() + removed_walls.read().count();
+    if changed_query.is_empty() && removed == 0 {
+        return;
+    }
+
+    let obstacles: Vec<NavObstacle> = obstacle_query
+        .iter()
+        .filter_map(|(transform, collider, is_wall)| {
+            let half_extents = collider.as_cuboid()?.half_extents() * transform.scale;
+            Some(NavObstacle {
+                center: transform.translation,
+                // Size of the box as seen from above, whichever way it is turned
+                half_extents: Mat3::from_quat(transform.rotation).abs() * half_extents,
+                walkable_top: !is_wall,
+            })
+        })
+        .collect();
+    if obstacles.is_empty() {
+        *nav_grid = NavGrid::default();
+        return;
+    }
+    let (min, max) = obstacles.iter().fold((Vec2::MAX, Vec2::MIN), |(min, max), obstacle| {
+        let footprint = obstacle.half_extents.xz();
+        (min.min(obstacle.center.xz() - footprint), max.max(obstacle.center.xz() + footprint))
+    });
+    *nav_grid = NavGrid::build(min, max, &obstacles);
+}
+
+/// Shows or hides the navigation overlay when N is pressed
+pub fn toggle_nav_debug_system(keyboard_input: Res<ButtonInput<KeyCode>>, mut nav_debug: ResMut<NavDebug>) {
+    if keyboard_input.just_pressed(KeyCode::KeyN) {
+        nav_debug.visible = !nav_debug.visible;
+    }
+}
+
+/// Draws the walkable cells of the navigation grid, raised surfaces in another color, and the path each bot follows
+pub fn draw_nav_debug_system(
+    nav_debug: Res<NavDebug>,
+    nav_grid: Res<NavGrid>,
+    bot_query: Query<(&Transform, &Bot)>,
+    mut gizmos: Gizmos,
+) {
+    // Lifts the overlay off the surfaces so it isn't hidden inside them
+    const LIFT: f32 = 0.05;
+
+    if !nav_debug.visible {
+        return;
+    }
+    // The lowest surface is the ground, everything above it is a structure
+    let ground = nav_grid
+        .cells
+        .iter()
+        .filter_map(|cell| match cell {
+            NavCell::Walkable(height) => Some(*height),
+            NavCell::Blocked => None,
+        })
+        .fold(f32::MAX, f32::min);
+
+    for index in 0..nav_grid.cells.len() {
+        if !nav_grid.is_walkable(index) {
+            continue;
+        }
+        let position = nav_grid.cell_position(index);
+        let color = if position.y > ground + 0.1 { CYAN_400 } else { LIME };
+        gizmos.rect(
+            Isometry3d::new(position + Vec3::Y * LIFT, Quat::from_rotation_x(FRAC_PI_2)),
+            Vec2::splat(NAV_CELL_SIZE * 0.9),
+            color,
+        );
+    }
+
+    for (transform, bot) in bot_query.iter() {
+        if bot.path.is_empty() {
+            continue;
+        }
+        let points = std::iter::once(transform.translation)
+            .chain(bot.path.iter().copied())
+            .map(|point| point + Vec3::Y * LIFT);
+        gizmos.linestrip(points, YELLOW);
+    }
+}
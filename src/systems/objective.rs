@@ -0,0 +1,168 @@
+use crate::components::combat::Respawning;
+use crate::components::objective::{CaptureZone, CAPTURE_TIME};
+use crate::components::player::{LocalPlayer, Player};
+use crate::components::score::Score;
+use crate::components::world::TriggerVolume;
+use crate::events::notification::{NotificationEvent, NotificationPriority};
+use crate::resources::game::{GameMode, GameState, MatchSettings};
+use bevy::color::palettes::tailwind::{GREEN_400, ORANGE_500, RED_500};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Keeps track of what is inside each trigger volume from the sensor events Rapier sends
+pub fn trigger_volume_system(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut trigger_query: Query<&mut TriggerVolume>,
+) {
+    for event in collision_events.read() {
+        let (entity1, entity2, started) = match event {
+            CollisionEvent::Started(entity1, entity2, _) => (*entity1, *entity2, true),
+            CollisionEvent::Stopped(entity1, entity2, _) => (*entity1, *entity2, false),
+        };
+        for (trigger, other) in [(entity1, entity2), (entity2, entity1)] {
+            let Ok(mut volume) = trigger_query.get_mut(trigger) else {
+                continue;
+            };
+            if !started {
+                volume.inside.retain(|entity| *entity != other);
+            } else if !volume.inside.contains(&other) {
+                volume.inside.push(other);
+            }
+        }
+    }
+}
+
+/// Puts the hill back at its first position, and turns it on only for king of the hill matches
+pub fn reset_capture_zone_system(
+    state: Res<State<GameState>>,
+    settings: Res<MatchSettings>,
+    mut zone_query: Query<(&mut CaptureZone, &mut Transform)>,
+) {
+    let active = *state.get() == GameState::InProgress && settings.mode == GameMode::KingOfTheHill;
+    for (mut zone, mut transform) in zone_query.iter_mut() {
+        zone.move_to(0);
+        zone.rotation_timer.reset();
+        zone.active = active;
+        transform.translation = zone.center();
+    }
+}
+
+/**
+Captures and scores the hill.
+A living player alone on the hill captures it, and once it is theirs scores every second they stay alone on it.
+Nothing changes while the hill is empty or contested.
+*/
+pub fn capture_zone_system(
+    time: Res<Time>,
+    mut zone_query: Query<(&mut CaptureZone, &TriggerVolume)>,
+    mut player_query: Query<&mut Score, (With<Player>, Without<Respawning>)>,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    for (mut zone, volume) in zone_query.iter_mut() {
+        if !zone.active {
+            continue;
+        }
+        let occupants: Vec<Entity> = volume
+            .inside
+            .iter()
+            .copied()
+            .filter(|entity| player_query.contains(*entity))
+            .collect();
+
+        let contested = occupants.len() > 1;
+        if contested && !zone.contested {
+            for &player in occupants.iter() {
+                notification_events.send(NotificationEvent::new(
+                    player,
+                    "The hill is contested!",
+                    NotificationPriority::Normal,
+                ));
+            }
+        }
+        zone.contested = contested;
+        let [holder] = occupants[..] else {
+            continue;
+        };
+
+        if zone.owner == Some(holder) {
+            if zone.hold_timer.tick(time.delta()).just_finished() {
+                if let Ok(mut score) = player_query.get_mut(holder) {
+                    score.record_hold();
+                }
+            }
+            continue;
+        }
+
+        if zone.capturer != Some(holder) {
+            zone.capturer = Some(holder);
+            zone.progress = 0.0;
+        }
+        zone.progress += time.delta_secs() / CAPTURE_TIME;
+        if zone.progress < 1.0 {
+            continue;
+        }
+        if let Some(previous) = zone.owner {
+            notification_events.send(NotificationEvent::new(previous, "You lost the hill", NotificationPriority::High));
+        }
+        notification_events.send(NotificationEvent::new(holder, "You hold the hill", NotificationPriority::Normal));
+        zone.owner = Some(holder);
+        zone.capturer = None;
+        zone.progress = 0.0;
+        zone.hold_timer.reset();
+    }
+}
+
+/// Moves the hill to its next position every so often, it has to be captured again there
+pub fn rotate_capture_zone_system(
+    time: Res<Time>,
+    mut zone_query: Query<(&mut CaptureZone, &mut Transform)>,
+    player_query: Query<Entity, With<Player>>,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    for (mut zone, mut transform) in zone_query.iter_mut() {
+        if !zone.active || !zone.rotation_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let next = zone.current + 1;
+        zone.move_to(next);
+        transform.translation = zone.center();
+        for player in player_query.iter() {
+            notification_events.send(NotificationEvent::new(player, "The hill has moved", NotificationPriority::High));
+        }
+    }
+}
+
+/// Colors the hill by who holds it and hides it when the match isn't played for it
+pub fn capture_zone_visual_system(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    local_query: Query<Entity, With<LocalPlayer>>,
+    mut zone_query: Query<(&CaptureZone, &MeshMaterial3d<StandardMaterial>, &mut Visibility)>,
+) {
+    let local_player = local_query.get_single().ok();
+    for (zone, material, mut visibility) in zone_query.iter_mut() {
+        visibility.set_if_neq(if zone.active { Visibility::Inherited } else { Visibility::Hidden });
+
+        let color = hill_color(zone, local_player).with_alpha(0.3);
+        let current = materials.get(&material.0).map(|material| material.base_color);
+        if current.is_some_and(|current| current != color) {
+            if let Some(material) = materials.get_mut(&material.0) {
+                material.base_color = color;
+            }
+        }
+    }
+}
+
+// HELPER FUNCTION
+
+/// Helper function to pick the color of the hill, red while contested and green while the local player holds it
+pub fn hill_color(zone: &CaptureZone, local_player: Option<Entity>) -> Color {
+    if zone.contested {
+        Color::from(RED_500)
+    } else if zone.owner.is_some() && zone.owner == local_player {
+        Color::from(GREEN_400)
+    } else if zone.owner.is_some() {
+        Color::from(ORANGE_500)
+    } else {
+        Color::WHITE
+    }
+}
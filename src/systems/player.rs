@@ -1,8 +1,19 @@
+use crate::components::combat::{
+    Blocking, DamageSource, Fuse, Instigator, MeleeSwing, ParryCooldown, Respawning, Slowed, Staggered, Stunned,
+    ThrowParams,
+};
 use crate::components::{player::*, world::*};
+use crate::events::combat::AttackEvent;
+use crate::events::notification::{NotificationEvent, NotificationPriority};
+use crate::resources::item::ItemAssets;
+use crate::resources::rules::MatchRules;
+use crate::spawns::item::spawn_dropped_item;
+use crate::spawns::projectile::spawn_thrown_object;
 // use crate::resources::game::GameState;
 // use crate::resources::DebugPrintTimer;
-use bevy::input::{ButtonInput, ButtonState};
-use bevy::input::mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel};
+use std::collections::HashSet;
+use bevy::input::ButtonInput;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::math::Vec3;
 use bevy::prelude::*;
 use bevy::text::cosmic_text::Scroll;
@@ -10,29 +21,26 @@ use bevy_rapier3d::prelude::*;
 use bevy_rapier3d::rapier::prelude::CollisionEventFlags;
 
 /**
-Read the keyboard event
+Fills the local player's intent from the keyboard and mouse.
+WASD walks relative to where the player faces, the mouse buttons attack, fire, block and throw.
 */
-pub fn player_movement_system(
+pub fn player_input_system(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    time: Res<Time>,
-    mut player_query: Query<
-        (
-            &mut Transform,
-            &PlayerStats,
-            &mut Velocity,
-            &mut ExternalImpulse,
-            Option<&mut JumpAbility>,
-        ),
-        With<Player>,
-    >,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut scroll_events: EventReader<MouseWheel>,
+    mut player_query: Query<(&Transform, &mut PlayerIntent, Has<Respawning>), With<LocalPlayer>>,
 ) {
-    let Ok((mut transform, movement, mut v, mut impulse, jump_ability_opt)) =
-        player_query.get_single_mut()
-    else {
+    let cycle_item = scroll_events
+        .read()
+        .filter(|event| event.unit == MouseScrollUnit::Line)
+        .map(|event| event.y.signum() as i32)
+        .sum();
+
+    let Ok((transform, mut intent, is_respawning)) = player_query.get_single_mut() else {
         return;
     };
-    let mut input_dir = Vec3::ZERO;
 
+    let mut input_dir = Vec3::ZERO;
     if keyboard_input.pressed(KeyCode::KeyW) {
         input_dir.z -= 1.0
     }
@@ -45,114 +53,389 @@ pub fn player_movement_system(
     if keyboard_input.pressed(KeyCode::KeyD) {
         input_dir.x += 1.0
     }
+    // Get movement direction based on transform yaw, without any Y-Axis movement
+    let movement = (transform.rotation * input_dir.normalize_or_zero()).with_y(0.0).normalize_or_zero();
+
+    let switch_weapon = if keyboard_input.just_pressed(KeyCode::Digit1) {
+        Some(WeaponSlotKind::Primary)
+    } else if keyboard_input.just_pressed(KeyCode::Digit2) {
+        Some(WeaponSlotKind::Secondary)
+    } else if keyboard_input.just_pressed(KeyCode::Digit3) {
+        Some(WeaponSlotKind::Melee)
+    } else {
+        None
+    };
+
+    *intent = PlayerIntent {
+        movement,
+        // The camera aims for the local player
+        look: None,
+        // Movement runs on fixed ticks, so a jump waits for the next one instead of falling between them.
+        // Dead players don't move, so their jump would wait until they respawn
+        jump: !is_respawning && (keyboard_input.just_pressed(KeyCode::Space) || intent.jump),
+        attack: mouse_input.just_pressed(MouseButton::Left),
+        fire: mouse_input.pressed(MouseButton::Left),
+        block: mouse_input.pressed(MouseButton::Right),
+        throw: mouse_input.just_pressed(MouseButton::Right),
+        reload: keyboard_input.just_pressed(KeyCode::KeyR),
+        use_item: keyboard_input.just_pressed(KeyCode::KeyE),
+        drop_item: keyboard_input.just_pressed(KeyCode::KeyQ),
+        switch_weapon,
+        cycle_item,
+    };
+}
 
-    // Check if it is jumping
-    if let Some(mut jump_ability) = jump_ability_opt {
-        if keyboard_input.just_pressed(KeyCode::Space) && !jump_ability.is_jumping {
-            v.linvel.y = 0.0;
-            impulse.impulse = Vec3::new(0.0, 10.0, 0.0);
-            jump_ability.is_jumping = true;
+/// Turns players towards where their intent looks, the yaw turns the body and the aim keeps the pitch
+pub fn player_aim_system(mut player_query: Query<(&PlayerIntent, &mut Transform, &mut Direction), With<Player>>) {
+    for (intent, mut transform, mut direction) in player_query.iter_mut() {
+        let Some(look) = intent.look.and_then(Vec3::try_normalize) else {
+            continue;
+        };
+        direction.direction = look;
+        let flat = look.with_y(0.0);
+        if flat != Vec3::ZERO {
+            transform.look_to(flat, Vec3::Y);
         }
-        // else if !jump_ability.is_jumping {
-        //         v.linvel.y = 0.0;
-        // }
     }
+}
+
+/// Moves players along their intended direction and lets them jump when they are on the ground, once every fixed tick
+pub fn player_movement_system(
+    rules: Res<MatchRules>,
+    mut player_query: Query<
+        (
+            &mut PlayerIntent,
+            &PlayerStats,
+            &mut Velocity,
+            &mut ExternalImpulse,
+            Option<&mut JumpAbility>,
+            Option<&Slowed>,
+            Option<&Staggered>,
+            Has<Blocking>,
+        ),
+        (With<Player>, Without<Respawning>),
+    >,
+) {
+    // Fraction of the speed kept while holding up a block
+    const BLOCK_SPEED_FACTOR: f32 = 0.5;
 
-    // if it is not jumping
+    for (mut intent, movement, mut v, mut impulse, jump_ability_opt, slowed, staggered, is_blocking) in
+        player_query.iter_mut()
+    {
+        // A jump is used up by the tick it lands on, whether or not the player could jump
+        let jump = std::mem::take(&mut intent.jump);
 
-    // Check if the direction input is applied
-    if input_dir != Vec3::ZERO {
-        input_dir = input_dir.normalize();
+        // Let knockback play out instead of overriding the velocity it gave
+        if staggered.is_some() {
+            continue;
+        }
 
-        // Get movement direction based on transform yaw
-        let rotation = transform.rotation;
-        let mut move_direction = rotation * input_dir;
+        // Check if it is jumping
+        if let Some(mut jump_ability) = jump_ability_opt {
+            if jump && !jump_ability.is_jumping {
+                v.linvel.y = 0.0;
+                impulse.impulse += Vec3::new(0.0, rules.jump_impulse, 0.0);
+                jump_ability.is_jumping = true;
+            }
+        }
 
         // Prevent Y-Axis movement
-        move_direction.y = 0.0;
-
-        // Update velocity based on input
-        let dx = move_direction.x * movement.speed;
-        let dz = move_direction.z * movement.speed;
-        v.linvel = Vec3::new(dx, v.linvel.y, dz);
-    } else {
-        // No input detected; set velocity to zero
-        v.linvel = Vec3::new(0.0, v.linvel.y, 0.0);
+        let move_direction = intent.movement.with_y(0.0).normalize_or_zero();
+        if move_direction != Vec3::ZERO {
+            // Update velocity based on input
+            let mut speed = movement.speed * slowed.map_or(1.0, |slowed| slowed.factor);
+            if is_blocking {
+                speed *= BLOCK_SPEED_FACTOR;
+            }
+            let dx = move_direction.x * speed;
+            let dz = move_direction.z * speed;
+            v.linvel = Vec3::new(dx, v.linvel.y, dz);
+        } else {
+            // No input detected; set velocity to zero
+            v.linvel = Vec3::new(0.0, v.linvel.y, 0.0);
+        }
     }
 }
 
+/**
+Use the selected item when the player intends to.
+Active items with a use time start a channel instead of applying right away,
+weapon items are applied instantly and passive items can't be used.
+*/
 pub fn use_item_system(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
     mut commands: Commands,
     time: Res<Time>,
-    mut player_query: Query<(Entity, &mut Inventory, &mut PlayerStats, Option<&mut Transform>, Option<&Direction>), With<Player>>,
+    mut player_query: Query<
+        (
+            Entity,
+            &PlayerIntent,
+            &EntityName,
+            &mut Inventory,
+            &mut PlayerStats,
+            &mut ItemCooldowns,
+            Option<&Transform>,
+            Option<&Direction>,
+            Option<&ItemChannel>,
+        ),
+        (With<Player>, Without<Stunned>, Without<Respawning>),
+    >,
+    mut notification_events: EventWriter<NotificationEvent>,
 ) {
-    for (entity, mut inventory, mut player_stats, transform, direction) in player_query.iter_mut() {
-        if keyboard_input.just_pressed(KeyCode::KeyE) {
-            inventory.use_item(&mut player_stats, transform, direction, &mut commands, entity, &time);
+    for (entity, intent, name, mut inventory, mut player_stats, mut cooldowns, transform, direction, channel) in
+        player_query.iter_mut()
+    {
+        // Already busy channeling another item
+        if !intent.use_item || channel.is_some() {
+            continue;
+        }
+        let Some(item) = inventory.selected_item().cloned() else {
+            continue;
+        };
+        if !cooldowns.is_ready(&item) {
+            notification_events.send(NotificationEvent::new(
+                entity,
+                format!("'{}' is on cooldown", item.name),
+                NotificationPriority::Low,
+            ));
+            continue;
+        }
+
+        match item.type_ {
+            ItemType::Active if item.use_time > 0.0 => {
+                commands.entity(entity).insert(ItemChannel {
+                    slot: inventory.current_selected_item,
+                    timer: Timer::from_seconds(item.use_time, TimerMode::Once),
+                    start_position: transform.map(|t| t.translation).unwrap_or_default(),
+                    start_health: player_stats.health,
+                });
+            }
+            ItemType::Passive => {
+                notification_events.send(NotificationEvent::new(
+                    entity,
+                    format!("'{}' works while it is in your inventory", item.name),
+                    NotificationPriority::Low,
+                ));
+            }
+            ItemType::Active | ItemType::Weapon => {
+                let user = Instigator::new(entity, name);
+                let used = inventory.use_item(&mut player_stats, transform, direction, &mut commands, &user);
+                finish_item_use(&mut commands, &mut cooldowns, &item, used, time.elapsed_secs());
+            }
         }
     }
 }
 
-pub fn change_selected_item_system(
-    mut inventory_query: Query<&mut Inventory, With<Player>>,
-    mut scroll_events: EventReader<MouseWheel>,
+/// Advances item channels, applying the item once the channel completes.
+/// The channel is interrupted when the player moves away or takes damage.
+pub fn item_channel_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_query: Query<
+        (
+            Entity,
+            &EntityName,
+            &mut Inventory,
+            &mut PlayerStats,
+            &mut ItemCooldowns,
+            &mut ItemChannel,
+            &Transform,
+            Option<&Direction>,
+        ),
+        With<Player>,
+    >,
+    mut notification_events: EventWriter<NotificationEvent>,
 ) {
-    for mut inventory in inventory_query.iter_mut() {
-        for event in scroll_events.read() {
-            match event.unit {
-                MouseScrollUnit::Line => {
-                    if event.y > 0.0 {
-                        // Scroll up: Move to the next item
-                        inventory.current_selected_item =
-                            (inventory.current_selected_item + 1) % inventory.slots.len();
-                    } else if event.y < 0.0 {
-                        // Scroll down: Move to the previous item (wrapping around)
-                        inventory.current_selected_item = (inventory.current_selected_item + inventory.slots.len() - 1) % inventory.slots.len();
-                    }
-                    // println!(
-                    //     "Selected item index: {}",
-                    //     inventory.current_selected_item
-                    // );
-                }
-                _ => {}
-            }
+    // How far the player may drift while channeling, so small physics jitter doesn't cancel it
+    const MAX_CHANNEL_DRIFT: f32 = 0.25;
+
+    for (entity, name, mut inventory, mut player_stats, mut cooldowns, mut channel, transform, direction) in
+        player_query.iter_mut()
+    {
+        let drift = (transform.translation - channel.start_position).with_y(0.0).length();
+        if drift > MAX_CHANNEL_DRIFT || player_stats.health < channel.start_health {
+            notification_events.send(NotificationEvent::new(entity, "Item use interrupted!", NotificationPriority::Normal));
+            commands.entity(entity).remove::<ItemChannel>();
+            continue;
         }
+
+        if !channel.timer.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let slot = channel.slot;
+        commands.entity(entity).remove::<ItemChannel>();
+        let Some(item) = inventory.slots.get(slot).cloned().flatten() else {
+            continue;
+        };
+        let user = Instigator::new(entity, name);
+        let used = inventory.use_item_at(slot, &mut player_stats, Some(transform), direction, &mut commands, &user);
+        finish_item_use(&mut commands, &mut cooldowns, &item, used, time.elapsed_secs());
+    }
+}
+
+pub fn item_cooldown_system(
+    time: Res<Time>,
+    mut cooldown_query: Query<&mut ItemCooldowns>,
+) {
+    for mut cooldowns in cooldown_query.iter_mut() {
+        cooldowns.tick(time.delta());
     }
 }
 
+/// Scrolls through the inventory slots, wrapping around at both ends
+pub fn change_selected_item_system(mut inventory_query: Query<(&PlayerIntent, &mut Inventory), With<Player>>) {
+    for (intent, mut inventory) in inventory_query.iter_mut() {
+        if intent.cycle_item == 0 {
+            continue;
+        }
+        let slots = inventory.slots.len() as i32;
+        let selected = (inventory.current_selected_item as i32 + intent.cycle_item).rem_euclid(slots);
+        inventory.current_selected_item = selected as usize;
+    }
+}
+
+/// Swings the held melee weapon on attack, spawning a short lived hit area in front of the player
 pub fn melee_system (
-    mut mouse_input: EventReader<MouseButtonInput>,
-    mut inventory_query: Query<&mut Inventory, With<Player>>,
+    mut commands: Commands,
+    mut inventory_query: Query<
+        (Entity, &PlayerIntent, &EntityName, &Transform, &Direction, &mut Inventory),
+        (With<Player>, Without<WeaponSwitch>, Without<Stunned>, Without<Blocking>, Without<Respawning>),
+    >,
 ) {
-    for event in mouse_input.read() {
-        match (event.button, event.state)  {
-            (MouseButton::Left, ButtonState::Pressed) => {
-                if let Ok(mut inventory) = inventory_query.get_single_mut() {
-                    if let Some(ref mut weapon) = inventory.weapon {
-                        weapon.decrement_durability();
-                        println!("Weapon durability: {}", weapon.durability);
-                    }
-                }
+    // How far in front of the player the swing reaches
+    const SWING_REACH: f32 = 1.2;
+    const SWING_RADIUS: f32 = 1.0;
+    const SWING_DURATION: f32 = 0.15;
+
+    for (entity, intent, name, transform, direction, mut inventory) in inventory_query.iter_mut() {
+        if !intent.attack {
+            continue;
+        }
+        // Ranged weapons fire instead, see `fire_weapon_system`
+        let Some(weapon) = inventory.weapons.active_weapon_mut().filter(|weapon| weapon.ranged.is_none()) else {
+            continue;
+        };
+        weapon.decrement_durability();
+        commands.send_event(AttackEvent { attacker: entity });
+
+        let forward = direction.direction.with_y(0.0).normalize_or_zero();
+        commands.spawn((
+            Transform::from_translation(transform.translation + forward * SWING_REACH),
+            MeleeSwing {
+                timer: Timer::from_seconds(SWING_DURATION, TimerMode::Once),
+                damage: weapon.damage,
+                damage_type: weapon.damage_type,
+                radius: SWING_RADIUS,
+                origin: transform.translation,
+                knockback: (forward + Vec3::Y * 0.3) * weapon.knockback,
+                hit_stun: weapon.hit_stun,
+                hits: Vec::new(),
+            },
+            DamageSource::new(Instigator::new(entity, name), &weapon.name),
+        ));
+    }
+}
+
+/// Holds up a block while the player intends to and is holding a melee weapon that can't be thrown.
+/// A block raised during the parry cooldown of the last one can't parry.
+pub fn block_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_query: Query<
+        (
+            Entity,
+            &PlayerIntent,
+            &Inventory,
+            Option<&mut Blocking>,
+            Option<&mut ParryCooldown>,
+            Has<Stunned>,
+            Has<WeaponSwitch>,
+        ),
+        (With<Player>, Without<Respawning>),
+    >,
+) {
+    for (entity, intent, inventory, blocking, parry_cooldown, is_stunned, is_switching) in player_query.iter_mut() {
+        let mut parry_ready = true;
+        if let Some(mut cooldown) = parry_cooldown {
+            parry_ready = cooldown.timer.tick(time.delta()).finished();
+            if parry_ready {
+                commands.entity(entity).remove::<ParryCooldown>();
             }
-            _ => {}
+        }
+        let can_block = inventory
+            .weapons
+            .active_weapon()
+            .is_some_and(|weapon| weapon.ranged.is_none() && !weapon.throwable);
+        let wants_block = intent.block && can_block && !is_stunned && !is_switching;
+
+        match (wants_block, blocking) {
+            (true, Some(mut blocking)) => blocking.elapsed += time.delta_secs(),
+            (true, None) => {
+                commands.entity(entity).insert(Blocking::new(parry_ready));
+            }
+            (false, Some(_)) => {
+                commands.entity(entity).remove::<Blocking>().insert(ParryCooldown {
+                    timer: Timer::from_seconds(Blocking::PARRY_COOLDOWN, TimerMode::Once),
+                });
+            }
+            (false, None) => {}
         }
     }
 }
 
-pub fn check_weapon_durability_system(
-    mut inventory_query: Query<&mut Inventory, With<Player>>,
+/// Switches the held weapon to the slot the player asks for.
+/// The new weapon can only be used after a short delay.
+pub fn switch_weapon_system(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &PlayerIntent, &mut Inventory), With<Player>>,
 ) {
-    let Ok(mut inventory) = inventory_query.get_single_mut() else {
-        return;
-    };
+    // Seconds it takes to put the new weapon in hand
+    const SWITCH_DELAY: f32 = 0.4;
 
-    // check if the durability of the weapon is zero
-    if let Some(weapon) = &inventory.weapon {
-        if weapon.durability == 0 {
-            // Set the weapon to None
-            inventory.weapon = None;
-            println!("Weapon broken and removed from inventory!");
+    for (entity, intent, mut inventory) in player_query.iter_mut() {
+        let Some(requested) = intent.switch_weapon else {
+            continue;
+        };
+        if inventory.weapons.active == requested {
+            continue;
+        }
+        inventory.weapons.active = requested;
+        // Switching away cancels a reload in progress
+        commands.entity(entity).remove::<Reloading>().insert(WeaponSwitch {
+            timer: Timer::from_seconds(SWITCH_DELAY, TimerMode::Once),
+        });
+    }
+}
+
+pub fn weapon_switch_delay_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut switch_query: Query<(Entity, &mut WeaponSwitch)>,
+) {
+    for (entity, mut switch) in switch_query.iter_mut() {
+        if switch.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<WeaponSwitch>();
+        }
+    }
+}
+
+pub fn check_weapon_durability_system(
+    mut inventory_query: Query<(Entity, &mut Inventory), With<Player>>,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    for (entity, mut inventory) in inventory_query.iter_mut() {
+        // check if the durability of any weapon is zero
+        for slot in WeaponSlotKind::ALL {
+            if inventory.weapons.get(slot).is_some_and(|weapon| weapon.durability == 0) {
+                // Remove the weapon from the loadout
+                if let Some(weapon) = inventory.weapons.take(slot) {
+                    notification_events.send(NotificationEvent::new(
+                        entity,
+                        format!("'{}' broke!", weapon.name),
+                        NotificationPriority::High,
+                    ));
+                }
+            }
         }
     }
 }
@@ -162,13 +445,14 @@ pub fn speed_boost_system(
     mut commands: Commands,
     time: Res<Time>,
     mut player_query: Query<(Entity, &mut PlayerStats, &mut SpeedBoost)>,
+    mut notification_events: EventWriter<NotificationEvent>,
 ) {
     for (entity, mut player_stats, mut speed_boost) in player_query.iter_mut() {
         speed_boost.timer.tick(time.delta());
 
         if speed_boost.timer.finished() {
             player_stats.speed -= speed_boost.amount;
-            println!("Speed boost expired. Speed reset to {}", player_stats.speed);
+            notification_events.send(NotificationEvent::new(entity, "Speed boost expired", NotificationPriority::Normal));
             commands.entity(entity).remove::<SpeedBoost>();
         }
     }
@@ -230,24 +514,36 @@ pub fn update_jump_state_system(
 
 pub fn check_item_intersections(
     mut commands: Commands,
+    item_assets: Res<ItemAssets>,
     mut collider_events: EventReader<CollisionEvent>,
     mut player_query: Query<(Entity, &Transform, &Player, &EntityName, &mut PlayerStats, &mut Inventory)>,
-    mut item_query: Query<(Entity, &Item, &Transform)>,
+    mut item_query: Query<(Entity, &Item, &Transform), Without<PickupDelay>>,
 ) {
+    // Two players can touch the same item in one frame, only the first one gets it
+    let mut claimed = HashSet::new();
+
     for event in collider_events.read() {
         match event {
             CollisionEvent::Started(entity1, entity2, flag) if *flag == CollisionEventFlags::SENSOR => {
                 // Try player as entity1 and item as entity2
-                if let Some((player_name, mut inventory, item_entity, item)) =
+                if let Some((player, player_transform, mut inventory, item_entity, item)) =
                     try_get_player_and_item(*entity1, *entity2, &mut player_query, &mut item_query)
                 {
-                    handle_item_pickup(&mut commands, player_name, &mut inventory, item_entity, item);
+                    if !claimed.contains(&item_entity)
+                        && handle_item_pickup(&mut commands, &item_assets, player, player_transform, &mut inventory, item_entity, item)
+                    {
+                        claimed.insert(item_entity);
+                    }
                 }
                 // Try player as entity2 and item as entity1
-                else if let Some((player_name, mut inventory, item_entity, item)) =
+                else if let Some((player, player_transform, mut inventory, item_entity, item)) =
                     try_get_player_and_item(*entity2, *entity1, &mut player_query, &mut item_query)
                 {
-                    handle_item_pickup(&mut commands, player_name, &mut inventory, item_entity, item);
+                    if !claimed.contains(&item_entity)
+                        && handle_item_pickup(&mut commands, &item_assets, player, player_transform, &mut inventory, item_entity, item)
+                    {
+                        claimed.insert(item_entity);
+                    }
                 }
             }
             _ => {}
@@ -255,45 +551,132 @@ pub fn check_item_intersections(
     }
 }
 
-pub fn threw_item_system(
+/// Picks up items within the player's pickup radius without touching them.
+/// Weapons still have to be touched, otherwise a swapped weapon would be pulled right back.
+pub fn pickup_radius_system(
+    mut commands: Commands,
+    item_assets: Res<ItemAssets>,
+    mut player_query: Query<(Entity, &Transform, &PlayerStats, &mut Inventory), (With<Player>, Without<Respawning>)>,
+    item_query: Query<(Entity, &Item, &Transform), Without<PickupDelay>>,
+) {
+    // Items are only despawned once the commands run, so one taken this frame is still in the query
+    let mut claimed = HashSet::new();
+
+    for (player, player_transform, player_stats, mut inventory) in player_query.iter_mut() {
+        if player_stats.pickup_radius <= 0.0 {
+            continue;
+        }
+        for (item_entity, item, item_transform) in item_query.iter() {
+            if !inventory.has_space() {
+                break;
+            }
+            if item.type_ == ItemType::Weapon || claimed.contains(&item_entity) {
+                continue;
+            }
+            let distance = player_transform.translation.distance(item_transform.translation);
+            if distance <= player_stats.pickup_radius
+                && handle_item_pickup(&mut commands, &item_assets, player, player_transform, &mut inventory, item_entity, item)
+            {
+                claimed.insert(item_entity);
+            }
+        }
+    }
+}
+
+pub fn pickup_delay_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut threw_item_query: Query<(Entity, &ThrewObject)>,
-    ground_query: Query<(Entity, &Ground)>,
-    mut player_query: Query<(Entity, &mut PlayerStats), With<Player>>,
-    mut collider_events: EventReader<CollisionEvent>,
+    mut delay_query: Query<(Entity, &mut PickupDelay)>,
 ) {
-    let current_time = time.elapsed_secs(); // Get the current game time
+    for (entity, mut delay) in delay_query.iter_mut() {
+        if delay.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<PickupDelay>();
+        }
+    }
+}
 
-    for (entity, threw_object) in threw_item_query.iter_mut() {
-        // Check if the object has been alive for more than 5 seconds
-        if current_time - threw_object.spawn_time > 5.0 {
-            println!("Threw Object {} despawned due to timeout", entity);
-            commands.entity(entity).despawn_recursive();
+/// Drops the selected item in front of the player when they intend to
+pub fn drop_item_system(
+    mut commands: Commands,
+    item_assets: Res<ItemAssets>,
+    mut player_query: Query<
+        (Entity, &PlayerIntent, &Transform, &mut Inventory, Option<&ItemChannel>),
+        (With<Player>, Without<Respawning>),
+    >,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    for (entity, intent, transform, mut inventory, channel) in player_query.iter_mut() {
+        // Don't drop the item that is being channeled
+        if !intent.drop_item || channel.is_some_and(|channel| channel.slot == inventory.current_selected_item) {
             continue;
         }
+        let selected = inventory.current_selected_item;
+        let Some(item) = inventory.remove_item(selected) else {
+            continue;
+        };
+        notification_events.send(NotificationEvent::new(
+            entity,
+            format!("Dropped '{}'", item.name),
+            NotificationPriority::Low,
+        ));
+        spawn_dropped_item(&mut commands, &item_assets, item, drop_position(transform));
     }
+}
 
-    for event in collider_events.read() {
-        match event {
-            CollisionEvent::Started(entity1, entity2, flag)
-                if *flag == CollisionEventFlags::SENSOR  => {
-
-                        if let Ok((threw_object_entity, threw_object)) = threw_item_query.get(*entity1) {
-                            // if let Ok((player_entity, player_stats)) = player_query.get_mut(*entity2) {
-                            //     println!("Threw Object {} hit player {}", threw_object_entity, player_entity);
-                            //
-                            //     // destroy the object
-                            //     commands.entity(threw_object_entity).despawn_recursive();
-                            // }
-                            if let Ok((ground_entity, _)) = ground_query.get(*entity2) {
-                                println!("Threw Object {} hit ground {}", threw_object_entity, ground_entity);
-                                commands.entity(threw_object_entity).despawn_recursive();
-                            }
-                    }
-            }
-            _ => {}
+/// Recomputes the stats granted by passive items whenever the inventory changes.
+/// Only the difference to the previously applied modifiers is applied,
+/// so temporary buffs like [`SpeedBoost`] are kept intact.
+pub fn apply_passive_modifiers_system(
+    mut player_query: Query<(&Inventory, &mut PlayerStats, &mut AppliedModifiers), Changed<Inventory>>,
+) {
+    for (inventory, mut player_stats, mut applied) in player_query.iter_mut() {
+        let modifiers = inventory.passive_modifiers();
+        if modifiers == applied.0 {
+            continue;
+        }
+        player_stats.apply_modifier_delta(&applied.0, &modifiers);
+        applied.0 = modifiers;
+    }
+}
+
+/// Throws the held weapon when the player intends to and it is throwable
+pub fn throw_weapon_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    item_assets: Res<ItemAssets>,
+    rules: Res<MatchRules>,
+    mut player_query: Query<
+        (Entity, &PlayerIntent, &EntityName, &Transform, &Direction, &mut Inventory),
+        (With<Player>, Without<WeaponSwitch>, Without<Stunned>, Without<Respawning>),
+    >,
+) {
+    // How fast a thrown weapon spins around its sideways axis
+    const SPIN_SPEED: f32 = 15.0;
+
+    for (entity, intent, name, transform, direction, mut inventory) in player_query.iter_mut() {
+        let active = inventory.weapons.active;
+        if !intent.throw || !inventory.weapons.get(active).is_some_and(|weapon| weapon.throwable) {
+            continue;
         }
+        let Some(weapon) = inventory.weapons.take(active) else {
+            continue;
+        };
+        let spin_axis = direction.direction.cross(Vec3::Y).normalize_or_zero();
+        let projectile = spawn_thrown_object(
+            &mut commands,
+            ThrowParams::new(item_assets.mesh.clone(), item_assets.weapon_material.clone(), rules.throw_speed)
+                .with_spin(spin_axis * SPIN_SPEED)
+                .with_damage(weapon.damage)
+                .with_damage_type(weapon.damage_type)
+                .with_knockback(weapon.knockback)
+                .with_hit_stun(weapon.hit_stun),
+            DamageSource::new(Instigator::new(entity, name), &weapon.name),
+            transform.translation,
+            direction.direction,
+            time.elapsed_secs(),
+        );
+        commands.entity(projectile).insert(ThrownWeapon { weapon });
+        commands.send_event(AttackEvent { attacker: entity });
     }
 }
 
@@ -304,37 +687,185 @@ fn try_get_player_and_item<'a>(
     player_entity: Entity,
     item_entity: Entity,
     mut player_query: &'a mut Query<(Entity, &Transform, &Player, &EntityName, &mut PlayerStats, &mut Inventory)>,
-    mut item_query: &'a mut Query<(Entity, &Item, &Transform)>,
-) -> Option<(&'a EntityName, Mut<'a, Inventory>, Entity, &'a Item)> {
-    if let Ok((_, _, _, player_name, _, mut inventory)) = player_query.get_mut(player_entity) {
+    mut item_query: &'a mut Query<(Entity, &Item, &Transform), Without<PickupDelay>>,
+) -> Option<(Entity, &'a Transform, Mut<'a, Inventory>, Entity, &'a Item)> {
+    if let Ok((player, player_transform, _, _, _, mut inventory)) = player_query.get_mut(player_entity) {
         if let Ok((item_entity, item, _)) = item_query.get_mut(item_entity) {
-            return Some((player_name, inventory, item_entity, item));
+            return Some((player, player_transform, inventory, item_entity, item));
         }
     }
     None
 }
 
-/// Helper function to handle item pickup logic
+/// Helper function to put a used item on cooldown and spawn what it throws, items that were kept cost nothing
+fn finish_item_use(commands: &mut Commands, cooldowns: &mut ItemCooldowns, item: &Item, used: ItemUse, spawn_time: f32) {
+    match used {
+        ItemUse::Kept => return,
+        ItemUse::Consumed => {}
+        ItemUse::Thrown(throw) => spawn_item_throw(commands, throw, spawn_time),
+    }
+    cooldowns.start(item);
+}
+
+/// Helper function to spawn the object a throwable item throws, lit if the item has a fuse
+fn spawn_item_throw(commands: &mut Commands, throw: ItemThrow, spawn_time: f32) {
+    if let Some(instigator) = &throw.source.instigator {
+        commands.send_event(AttackEvent { attacker: instigator.entity });
+    }
+    let projectile = spawn_thrown_object(commands, throw.params, throw.source, throw.origin, throw.direction, spawn_time);
+    if let Some(fuse) = throw.fuse {
+        commands.entity(projectile).insert(Fuse::new(fuse));
+    }
+}
+
+/// Helper function to find where a player drops an item, just in front of them
+fn drop_position(player_transform: &Transform) -> Vec3 {
+    let forward = (player_transform.rotation * Vec3::NEG_Z).with_y(0.0).normalize_or_zero();
+    player_transform.translation + forward * 2.5
+}
+
+/// Helper function to handle item pickup logic, returns whether the item was taken out of the world
 fn handle_item_pickup(
     commands: &mut Commands,
-    player_name: &EntityName,
+    item_assets: &ItemAssets,
+    player: Entity,
+    player_transform: &Transform,
     inventory: &mut Mut<Inventory>,
     item_entity: Entity,
     item: &Item,
-) {
-    println!("Player {:?} collided with item '{}'", player_name, item.name);
+) -> bool {
+    // Ammo goes straight to a weapon that uses it
+    if let ItemEffect::Ammo { ammo_type, amount } = item.effect {
+        if inventory.weapons.add_ammo(ammo_type, amount) {
+            commands.send_event(NotificationEvent::new(
+                player,
+                format!("Picked up {} {:?} ammo", amount, ammo_type),
+                NotificationPriority::Low,
+            ));
+            commands.entity(item_entity).despawn_recursive();
+            return true;
+        }
+    }
+
+    // Weapons go straight into the loadout, swapping out the old weapon if it is full
+    if let ItemEffect::WeaponItem(weapon) = &item.effect {
+        if let Some(replaced) = inventory.weapons.equip(weapon.clone()) {
+            commands.send_event(NotificationEvent::new(
+                player,
+                format!("Swapped '{}' for '{}'", replaced.name, weapon.name),
+                NotificationPriority::Normal,
+            ));
+            spawn_dropped_item(commands, item_assets, replaced.into_item(), drop_position(player_transform));
+        }
+        commands.entity(item_entity).despawn_recursive();
+        return true;
+    }
 
     // Pick up the item
     match inventory.add_item(item.clone()) {
         Ok(()) => {
-            println!("Item '{}' added to inventory.", item.name);
+            commands.send_event(NotificationEvent::new(
+                player,
+                format!("Picked up '{}'", item.name),
+                NotificationPriority::Low,
+            ));
 
             // Remove item from the world
             commands.entity(item_entity).despawn_recursive();
+            true
         }
         Err(_) => {
-            println!("Inventory is full!");
+            commands.send_event(NotificationEvent::new(player, "Inventory is full!", NotificationPriority::Normal));
+            false
         }
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::combat::DamageType;
+    use std::time::Duration;
+
+    fn block_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>().add_systems(Update, block_system);
+        app
+    }
+
+    /// Spawns a player holding a sword, so they can block
+    fn spawn_swordsman(app: &mut App) -> Entity {
+        let mut inventory = Inventory::new();
+        inventory.weapons.equip(Weapon {
+            name: String::from("Sword"),
+            description: String::new(),
+            throwable: false,
+            durability: 10,
+            damage: 10.0,
+            damage_type: DamageType::Physical,
+            knockback: 0.0,
+            hit_stun: 0.0,
+            slot: WeaponSlotKind::Melee,
+            ranged: None,
+        });
+        inventory.weapons.active = WeaponSlotKind::Melee;
+        app.world_mut().spawn((Player, PlayerIntent::default(), inventory)).id()
+    }
+
+    /// Holds the block up or down for `seconds`
+    fn hold_block(app: &mut App, player: Entity, block: bool, seconds: f32) {
+        app.world_mut().get_mut::<PlayerIntent>(player).unwrap().block = block;
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_secs_f32(seconds));
+        app.update();
+    }
+
+    fn is_parrying(app: &App, player: Entity) -> bool {
+        app.world().get::<Blocking>(player).is_some_and(Blocking::is_parrying)
+    }
+
+    #[test]
+    fn hits_are_only_parried_right_after_raising_the_block() {
+        let mut app = block_app();
+        let player = spawn_swordsman(&mut app);
+
+        hold_block(&mut app, player, true, 0.0);
+        assert!(is_parrying(&app, player));
+
+        hold_block(&mut app, player, true, Blocking::PARRY_WINDOW * 0.5);
+        assert!(is_parrying(&app, player));
+
+        hold_block(&mut app, player, true, Blocking::PARRY_WINDOW);
+        assert!(app.world().get::<Blocking>(player).is_some());
+        assert!(!is_parrying(&app, player));
+    }
+
+    #[test]
+    fn raising_the_block_again_too_soon_does_not_parry() {
+        let mut app = block_app();
+        let player = spawn_swordsman(&mut app);
+        hold_block(&mut app, player, true, 0.0);
+
+        hold_block(&mut app, player, false, 0.0);
+        assert!(app.world().get::<Blocking>(player).is_none());
+        assert!(app.world().get::<ParryCooldown>(player).is_some());
+
+        hold_block(&mut app, player, true, Blocking::PARRY_COOLDOWN * 0.5);
+        assert!(app.world().get::<Blocking>(player).is_some());
+        assert!(!is_parrying(&app, player));
+    }
+
+    #[test]
+    fn parrying_works_again_once_the_cooldown_is_over() {
+        let mut app = block_app();
+        let player = spawn_swordsman(&mut app);
+        hold_block(&mut app, player, true, 0.0);
+        hold_block(&mut app, player, false, 0.0);
+
+        hold_block(&mut app, player, false, Blocking::PARRY_COOLDOWN);
+        assert!(app.world().get::<ParryCooldown>(player).is_none());
+
+        hold_block(&mut app, player, true, 0.0);
+        assert!(is_parrying(&app, player));
+    }
+}
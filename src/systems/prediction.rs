@@ -0,0 +1,62 @@
+use crate::components::combat::Respawning;
+use crate::components::player::{LocalPlayer, PlayerIntent};
+use crate::components::prediction::{PredictedMovement, CORRECTION_RATE, SNAP_DISTANCE};
+use crate::events::network::AuthoritativeStateEvent;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/**
+Rewinds the local player to the server's state and replays the inputs the server hasn't seen yet.
+Small errors between the prediction and the replay are smoothed out over the next ticks, large ones are snapped away.
+*/
+pub fn reconcile_system(
+    mut state_events: EventReader<AuthoritativeStateEvent>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut PredictedMovement), With<LocalPlayer>>,
+) {
+    for event in state_events.read() {
+        let Ok((mut transform, mut velocity, mut prediction)) = player_query.get_mut(event.player) else {
+            continue;
+        };
+        let Some(replayed) = prediction.acknowledge(event.sequence, event.position) else {
+            continue;
+        };
+
+        let error = replayed - transform.translation;
+        if error.length() > SNAP_DISTANCE {
+            transform.translation = replayed;
+            velocity.linvel = event.velocity;
+            prediction.last_position = replayed;
+            prediction.correction = Vec3::ZERO;
+        } else {
+            prediction.correction = error;
+        }
+    }
+}
+
+/// Buffers the local player's movement input for this tick and moves them a step closer to where the server puts them
+pub fn record_input_system(
+    mut player_query: Query<(&PlayerIntent, &mut Transform, &mut PredictedMovement), (With<LocalPlayer>, Without<Respawning>)>,
+) {
+    for (intent, mut transform, mut prediction) in player_query.iter_mut() {
+        let step = prediction.correction * CORRECTION_RATE;
+        if step != Vec3::ZERO {
+            transform.translation += step;
+            prediction.correction -= step;
+        }
+        // The correction isn't part of the movement the input caused
+        prediction.last_position = transform.translation;
+        prediction.push(intent.movement, intent.jump);
+    }
+}
+
+/// Stores how far the physics step moved the local player with the input of this tick
+pub fn record_displacement_system(
+    mut player_query: Query<(&Transform, &mut PredictedMovement), (With<LocalPlayer>, Without<Respawning>)>,
+) {
+    for (transform, mut prediction) in player_query.iter_mut() {
+        let displacement = transform.translation - prediction.last_position;
+        if let Some(frame) = prediction.pending.back_mut() {
+            frame.displacement = displacement;
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+use crate::components::combat::{DamageSource, Fuse};
+use crate::components::player::{Bullet, Player, ThrownWeapon, Weapon};
+use crate::components::world::{ClearingThrower, Ground, ThrewObject, THROWER_GRACE_TIME, WORLD_GROUP};
+use crate::events::combat::{DamageEvent, ProjectileImpactEvent};
+use crate::events::notification::{NotificationEvent, NotificationPriority};
+use crate::resources::item::ItemAssets;
+use crate::resources::rules::MatchRules;
+use crate::spawns::item::spawn_item_pickup;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_rapier3d::rapier::prelude::CollisionEventFlags;
+
+/**
+Turns contacts between thrown objects and anything else into [`ProjectileImpactEvent`]s.
+Contacts with the thrower are ignored.
+*/
+pub fn projectile_collision_system(
+    rapier_context: ReadDefaultRapierContext,
+    mut collision_events: EventReader<CollisionEvent>,
+    projectile_query: Query<(&DamageSource, &Transform, &Velocity), With<ThrewObject>>,
+    mut impact_events: EventWriter<ProjectileImpactEvent>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, flags) = event else {
+            continue;
+        };
+        if flags.contains(CollisionEventFlags::SENSOR) {
+            continue;
+        }
+        // The thrown object can be either side of the collision
+        let (projectile, hit) = if projectile_query.contains(*entity1) {
+            (*entity1, *entity2)
+        } else if projectile_query.contains(*entity2) {
+            (*entity2, *entity1)
+        } else {
+            continue;
+        };
+        let Ok((source, transform, velocity)) = projectile_query.get(projectile) else {
+            continue;
+        };
+        if source.is_from(hit) {
+            continue;
+        }
+
+        let (point, normal) = contact_point(rapier_context.single(), projectile, hit)
+            .unwrap_or((transform.translation, -velocity.linvel.normalize_or(Vec3::NEG_Y)));
+        impact_events.send(ProjectileImpactEvent {
+            projectile,
+            hit,
+            point,
+            normal,
+        });
+    }
+}
+
+/**
+Resolves projectile impacts.
+Players take damage and knockback, bullets break on any impact,
+and thrown weapons that land on the ground turn back into pickups.
+Everything else bounces off, fused throwables keep rolling until they go off.
+*/
+pub fn projectile_impact_system(
+    mut commands: Commands,
+    item_assets: Res<ItemAssets>,
+    mut impact_events: EventReader<ProjectileImpactEvent>,
+    mut projectile_query: Query<(
+        &mut ThrewObject,
+        &DamageSource,
+        &Transform,
+        &Velocity,
+        Option<&mut ThrownWeapon>,
+        Has<Bullet>,
+        Has<Fuse>,
+    )>,
+    ground_query: Query<(), With<Ground>>,
+    player_query: Query<(), With<Player>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    // Surfaces facing up at least this much count as floors to land on
+    const MIN_FLOOR_NORMAL_Y: f32 = 0.7;
+
+    // Objects despawned this frame, so they are not handled twice
+    let mut despawned = HashSet::new();
+
+    for impact in impact_events.read() {
+        if despawned.contains(&impact.projectile) {
+            continue;
+        }
+        let Ok((mut threw_object, source, transform, velocity, thrown_weapon, is_bullet, has_fuse)) =
+            projectile_query.get_mut(impact.projectile)
+        else {
+            continue;
+        };
+
+        if player_query.contains(impact.hit) {
+            if threw_object.has_hit {
+                continue;
+            }
+            threw_object.has_hit = true;
+            debug!("Threw Object {} hit player {} at {}", impact.projectile, impact.hit, impact.point);
+
+            // Push the target along the flight path and slightly up
+            let push = velocity.linvel.with_y(0.0).normalize_or(-impact.normal) + Vec3::Y * 0.3;
+            damage_events.send(DamageEvent {
+                target: impact.hit,
+                amount: threw_object.damage,
+                damage_type: threw_object.damage_type,
+                knockback: push * threw_object.knockback,
+                hit_stun: threw_object.hit_stun,
+                origin: transform.translation,
+                point: impact.point,
+                source: source.clone(),
+            });
+
+            if is_bullet {
+                commands.entity(impact.projectile).despawn_recursive();
+                despawned.insert(impact.projectile);
+            } else if let Some(mut thrown_weapon) = thrown_weapon {
+                thrown_weapon.weapon.decrement_durability();
+                if thrown_weapon.weapon.durability == 0 {
+                    if let Some(thrower) = source.instigator.as_ref() {
+                        commands.send_event(NotificationEvent::new(
+                            thrower.entity,
+                            format!("'{}' broke on impact!", thrown_weapon.weapon.name),
+                            NotificationPriority::High,
+                        ));
+                    }
+                    commands.entity(impact.projectile).despawn_recursive();
+                    despawned.insert(impact.projectile);
+                }
+            }
+        } else if is_bullet {
+            commands.entity(impact.projectile).despawn_recursive();
+            despawned.insert(impact.projectile);
+        } else if !has_fuse && ground_query.contains(impact.hit) && impact.normal.y >= MIN_FLOOR_NORMAL_Y {
+            debug!("Threw Object {} hit ground {} at {}", impact.projectile, impact.hit, impact.point);
+            if let Some(thrown_weapon) = thrown_weapon {
+                land_thrown_weapon(&mut commands, &item_assets, &thrown_weapon.weapon, transform);
+            }
+            commands.entity(impact.projectile).despawn_recursive();
+            despawned.insert(impact.projectile);
+        }
+    }
+}
+
+/// Removes thrown objects that have been flying for too long, thrown weapons land where they are
+pub fn projectile_timeout_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    item_assets: Res<ItemAssets>,
+    rules: Res<MatchRules>,
+    projectile_query: Query<(Entity, &ThrewObject, &Transform, Option<&ThrownWeapon>), Without<Fuse>>,
+) {
+    let current_time = time.elapsed_secs(); // Get the current game time
+
+    for (entity, threw_object, transform, thrown_weapon) in projectile_query.iter() {
+        if current_time - threw_object.spawn_time > rules.projectile_lifetime {
+            debug!("Threw Object {} despawned due to timeout", entity);
+            if let Some(thrown_weapon) = thrown_weapon {
+                land_thrown_weapon(&mut commands, &item_assets, &thrown_weapon.weapon, transform);
+            }
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Lets thrown objects push players once they had time to get clear of their thrower
+pub fn thrower_clearance_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut projectile_query: Query<(Entity, &ThrewObject, &mut SolverGroups), With<ClearingThrower>>,
+) {
+    for (entity, threw_object, mut solver_groups) in projectile_query.iter_mut() {
+        if time.elapsed_secs() - threw_object.spawn_time >= THROWER_GRACE_TIME {
+            *solver_groups = SolverGroups::new(Group::GROUP_3, Group::GROUP_1 | WORLD_GROUP);
+            commands.entity(entity).remove::<ClearingThrower>();
+        }
+    }
+}
+
+// HELPER FUNCTION
+
+/// Helper function to find where a projectile touches another collider and the surface normal there
+fn contact_point(context: &RapierContext, projectile: Entity, hit: Entity) -> Option<(Vec3, Vec3)> {
+    let pair = context.contact_pair(projectile, hit)?;
+    let manifold = pair.manifolds().find(|manifold| manifold.num_solver_contacts() > 0)?;
+    let point = manifold.solver_contact(0)?.point();
+    // The manifold normal points from the first collider of the pair to the second
+    let normal = if pair.collider1() == projectile {
+        -manifold.normal()
+    } else {
+        manifold.normal()
+    };
+    Some((point, normal))
+}
+
+/// Helper function to turn a thrown weapon back into a pickup where it landed
+fn land_thrown_weapon(
+    commands: &mut Commands,
+    item_assets: &ItemAssets,
+    weapon: &Weapon,
+    transform: &Transform,
+) {
+    // Keep the pickup above the ground even if the object sank into it
+    let position = transform.translation.with_y(transform.translation.y.max(0.75));
+    spawn_item_pickup(commands, item_assets, weapon.clone().into_item(), position);
+}
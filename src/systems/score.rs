@@ -0,0 +1,281 @@
+use crate::components::score::{RecentAttackers, Score};
+use crate::components::team::Team;
+use crate::events::combat::{DamageTakenEvent, DeathEvent};
+use crate::events::notification::{NotificationEvent, NotificationPriority};
+use bevy::prelude::*;
+
+/// Remembers who damaged each player, so they can be credited with an assist
+pub fn record_damage_system(
+    time: Res<Time>,
+    mut taken_events: EventReader<DamageTakenEvent>,
+    mut attackers_query: Query<&mut RecentAttackers>,
+) {
+    for event in taken_events.read() {
+        let Some(attacker) = event.source.instigator.as_ref().map(|instigator| instigator.entity) else {
+            continue;
+        };
+        if event.amount <= 0.0 || attacker == event.target {
+            continue;
+        }
+        if let Ok(mut recent_attackers) = attackers_query.get_mut(event.target) {
+            recent_attackers.record(attacker, event.amount, time.elapsed_secs());
+        }
+    }
+}
+
+/**
+Updates scores when a player dies.
+The victim gets a death, whoever dealt the final blow a kill
+and everyone else who recently damaged the victim an assist.
+Teammates of the victim get no credit, even with friendly fire on.
+*/
+pub fn score_system(
+    time: Res<Time>,
+    mut death_events: EventReader<DeathEvent>,
+    mut score_query: Query<(&mut Score, Option<&mut RecentAttackers>, Option<&Team>)>,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    // Streaks of this many kills or more are announced to the player
+    const ANNOUNCED_STREAK: u32 = 3;
+
+    for event in death_events.read() {
+        let killer = event
+            .source
+            .instigator
+            .as_ref()
+            .map(|instigator| instigator.entity)
+            .filter(|killer| *killer != event.victim);
+
+        let victim_team = score_query.get(event.victim).ok().and_then(|(.., team)| team.copied());
+        let assisters = match score_query.get_mut(event.victim) {
+            Ok((mut score, recent_attackers, _)) => {
+                score.record_death();
+                recent_attackers
+                    .map(|mut recent_attackers| {
+                        let assisters = recent_attackers.assisters(time.elapsed_secs());
+                        recent_attackers.0.clear();
+                        assisters
+                    })
+                    .unwrap_or_default()
+            }
+            Err(_) => Vec::new(),
+        };
+
+        if let Some(killer) = killer {
+            if let Ok((mut score, _, team)) = score_query.get_mut(killer) {
+                if Team::are_allies(team, victim_team.as_ref()) {
+                    notification_events.send(NotificationEvent::new(
+                        killer,
+                        format!("You killed your teammate {}", event.victim_name),
+                        NotificationPriority::High,
+                    ));
+                } else {
+                    score.record_kill();
+                    if score.streak >= ANNOUNCED_STREAK {
+                        notification_events.send(NotificationEvent::new(
+                            killer,
+                            format!("{} kill streak!", score.streak),
+                            NotificationPriority::High,
+                        ));
+                    }
+                }
+            }
+        }
+
+        for assister in assisters.into_iter().filter(|assister| Some(*assister) != killer) {
+            if let Ok((mut score, _, team)) = score_query.get_mut(assister) {
+                if Team::are_allies(team, victim_team.as_ref()) {
+                    continue;
+                }
+                score.record_assist();
+                notification_events.send(NotificationEvent::new(
+                    assister,
+                    format!("Assist on {}", event.victim_name),
+                    NotificationPriority::Normal,
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::combat::{DamageSource, Instigator};
+    use crate::components::world::EntityName;
+    use std::time::Duration;
+
+    fn score_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_event::<DamageTakenEvent>()
+            .add_event::<DeathEvent>()
+            .add_event::<NotificationEvent>()
+            .add_systems(Update, (record_damage_system, score_system).chain());
+        app
+    }
+
+    fn spawn_scored(app: &mut App) -> Entity {
+        app.world_mut().spawn((Score::default(), RecentAttackers::default())).id()
+    }
+
+    fn source(attacker: Entity) -> DamageSource {
+        DamageSource::new(Instigator::new(attacker, &EntityName::new("Attacker")), "Sword")
+    }
+
+    fn damage(app: &mut App, attacker: Entity, target: Entity, amount: f32) {
+        app.world_mut().send_event(DamageTakenEvent {
+            target,
+            amount,
+            origin: Vec3::ZERO,
+            point: Vec3::ZERO,
+            source: source(attacker),
+            lethal: false,
+        });
+        app.update();
+    }
+
+    fn kill(app: &mut App, killer: Entity, victim: Entity) {
+        app.world_mut().send_event(DeathEvent {
+            victim,
+            victim_name: String::from("Victim"),
+            source: source(killer),
+        });
+        app.update();
+    }
+
+    fn advance(app: &mut App, seconds: f32) {
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_secs_f32(seconds));
+    }
+
+    fn score(app: &App, player: Entity) -> &Score {
+        app.world().get::<Score>(player).unwrap()
+    }
+
+    #[test]
+    fn kill_credits_killer_and_victim() {
+        let mut app = score_app();
+        let killer = spawn_scored(&mut app);
+        let victim = spawn_scored(&mut app);
+
+        damage(&mut app, killer, victim, 100.0);
+        kill(&mut app, killer, victim);
+
+        assert_eq!(score(&app, killer).kills, 1);
+        assert_eq!(score(&app, killer).streak, 1);
+        assert_eq!(score(&app, killer).assists, 0);
+        assert_eq!(score(&app, victim).deaths, 1);
+        assert_eq!(score(&app, victim).kills, 0);
+    }
+
+    #[test]
+    fn streak_resets_on_death_and_best_streak_is_kept() {
+        let mut app = score_app();
+        let player = spawn_scored(&mut app);
+        let other = spawn_scored(&mut app);
+
+        for _ in 0..3 {
+            kill(&mut app, player, other);
+        }
+        assert_eq!(score(&app, player).streak, 3);
+
+        kill(&mut app, other, player);
+        assert_eq!(score(&app, player).streak, 0);
+        assert_eq!(score(&app, player).best_streak, 3);
+        assert_eq!(score(&app, player).deaths, 1);
+    }
+
+    #[test]
+    fn suicide_is_only_a_death() {
+        let mut app = score_app();
+        let player = spawn_scored(&mut app);
+
+        kill(&mut app, player, player);
+
+        assert_eq!(score(&app, player).kills, 0);
+        assert_eq!(score(&app, player).deaths, 1);
+    }
+
+    #[test]
+    fn recent_attackers_get_an_assist() {
+        let mut app = score_app();
+        let killer = spawn_scored(&mut app);
+        let assister = spawn_scored(&mut app);
+        let victim = spawn_scored(&mut app);
+
+        damage(&mut app, assister, victim, 40.0);
+        damage(&mut app, killer, victim, 60.0);
+        kill(&mut app, killer, victim);
+
+        assert_eq!(score(&app, assister).assists, 1);
+        assert_eq!(score(&app, assister).kills, 0);
+        // The killer gets the kill, not an assist on top
+        assert_eq!(score(&app, killer).assists, 0);
+        assert_eq!(score(&app, killer).kills, 1);
+    }
+
+    #[test]
+    fn grazes_do_not_earn_an_assist() {
+        let mut app = score_app();
+        let killer = spawn_scored(&mut app);
+        let assister = spawn_scored(&mut app);
+        let victim = spawn_scored(&mut app);
+
+        damage(&mut app, assister, victim, RecentAttackers::ASSIST_MIN_DAMAGE / 2.0);
+        kill(&mut app, killer, victim);
+
+        assert_eq!(score(&app, assister).assists, 0);
+    }
+
+    #[test]
+    fn damage_outside_the_assist_window_does_not_count() {
+        let mut app = score_app();
+        let killer = spawn_scored(&mut app);
+        let early = spawn_scored(&mut app);
+        let late = spawn_scored(&mut app);
+        let victim = spawn_scored(&mut app);
+
+        damage(&mut app, early, victim, 50.0);
+        advance(&mut app, RecentAttackers::ASSIST_WINDOW + 1.0);
+        damage(&mut app, late, victim, 50.0);
+        kill(&mut app, killer, victim);
+
+        assert_eq!(score(&app, early).assists, 0);
+        assert_eq!(score(&app, late).assists, 1);
+    }
+
+    #[test]
+    fn teammates_get_no_credit() {
+        let mut app = score_app();
+        let killer = app.world_mut().spawn((Score::default(), RecentAttackers::default(), Team::Red)).id();
+        let ally = app.world_mut().spawn((Score::default(), RecentAttackers::default(), Team::Red)).id();
+        let enemy = app.world_mut().spawn((Score::default(), RecentAttackers::default(), Team::Blue)).id();
+        let victim = app.world_mut().spawn((Score::default(), RecentAttackers::default(), Team::Red)).id();
+
+        damage(&mut app, ally, victim, 50.0);
+        damage(&mut app, enemy, victim, 50.0);
+        kill(&mut app, killer, victim);
+
+        assert_eq!(score(&app, killer).kills, 0);
+        assert_eq!(score(&app, killer).streak, 0);
+        assert_eq!(score(&app, ally).assists, 0);
+        // Enemies who helped still get their assist
+        assert_eq!(score(&app, enemy).assists, 1);
+        assert_eq!(score(&app, victim).deaths, 1);
+    }
+
+    #[test]
+    fn assists_are_not_credited_twice() {
+        let mut app = score_app();
+        let killer = spawn_scored(&mut app);
+        let assister = spawn_scored(&mut app);
+        let victim = spawn_scored(&mut app);
+
+        damage(&mut app, assister, victim, 50.0);
+        kill(&mut app, killer, victim);
+        // The victim respawned, the damage from their last life doesn't count again
+        kill(&mut app, killer, victim);
+
+        assert_eq!(score(&app, assister).assists, 1);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::components::combat::{Eliminated, Respawning, SpawnProtection, SpawnProtectionBubble};
+use crate::components::player::{Player, PlayerIntent, PlayerStats};
+use crate::components::team::Team;
+use crate::components::world::SpawnPoint;
+use crate::events::combat::{AttackEvent, DeathEvent};
+use crate::events::notification::{NotificationEvent, NotificationPriority};
+use crate::resources::game::{GameState, MatchSettings};
+use crate::resources::item::ItemAssets;
+use crate::resources::rng::GameRng;
+use crate::resources::rules::MatchRules;
+use crate::spawns::player::{respawn_player, PLAYER_SPAWN_POSITION};
+use crate::systems::area::is_occluded;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/**
+Takes dead players out of the arena until they respawn.
+In modes without respawns a player who dies during the match is eliminated instead.
+*/
+pub fn start_respawn_system(
+    mut commands: Commands,
+    state: Res<State<GameState>>,
+    settings: Res<MatchSettings>,
+    rules: Res<MatchRules>,
+    mut death_events: EventReader<DeathEvent>,
+    player_query: Query<(), (With<Player>, Without<Respawning>)>,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    let eliminating = *state.get() == GameState::InProgress && !settings.mode.has_respawns();
+    for event in death_events.read() {
+        if !player_query.contains(event.victim) {
+            continue;
+        }
+        commands.entity(event.victim).insert((
+            Respawning {
+                timer: Timer::from_seconds(rules.respawn_delay, TimerMode::Once),
+            },
+            Visibility::Hidden,
+            ColliderDisabled,
+            RigidBodyDisabled,
+            // Drop whatever the player was about to do, a latched jump would otherwise go off on respawn
+            PlayerIntent::default(),
+        ));
+        let message = if eliminating {
+            commands.entity(event.victim).insert(Eliminated);
+            String::from("You were eliminated")
+        } else {
+            format!("You died, respawning in {}s", rules.respawn_delay)
+        };
+        notification_events.send(NotificationEvent::new(event.victim, message, NotificationPriority::High));
+    }
+}
+
+/// Spawns dead players again once their respawn timer finishes, at the safest spawn point
+pub fn respawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    (item_assets, rules): (Res<ItemAssets>, Res<MatchRules>),
+    mut rng: ResMut<GameRng>,
+    rapier_context: ReadDefaultRapierContext,
+    spawn_point_query: Query<(&Transform, &SpawnPoint), Without<Player>>,
+    mut player_query: Query<
+        (Entity, &Transform, &PlayerStats, Option<&Team>, Option<&mut Respawning>),
+        (With<Player>, Without<Eliminated>),
+    >,
+) {
+    let spawn_points: Vec<(Transform, Option<Team>)> = spawn_point_query
+        .iter()
+        .map(|(transform, spawn_point)| (*transform, spawn_point.team))
+        .collect();
+    let mut living: Vec<(Vec3, Option<Team>)> = player_query
+        .iter()
+        .filter(|(_, _, player_stats, _, respawning)| respawning.is_none() && player_stats.health > 0.0)
+        .map(|(_, transform, _, team, _)| (transform.translation, team.copied()))
+        .collect();
+
+    for (player, _, _, team, respawning) in player_query.iter_mut() {
+        let Some(mut respawning) = respawning else {
+            continue;
+        };
+        if !respawning.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        let team = team.copied();
+        let spawn = choose_spawn_point(rapier_context.single(), &mut rng, &spawn_points, team, &living);
+        respawn_player(&mut commands, &item_assets, &rules, player, spawn);
+        // Players spawning in the same frame shouldn't end up on the same spot
+        living.push((spawn.translation, team));
+    }
+}
+
+/// Ends spawn protection when it runs out or the player attacks, hit or miss, removing its bubble
+pub fn spawn_protection_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut attack_events: EventReader<AttackEvent>,
+    mut protected_query: Query<(Entity, &mut SpawnProtection, &Children)>,
+    bubble_query: Query<(), With<SpawnProtectionBubble>>,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    let attackers: Vec<Entity> = attack_events.read().map(|event| event.attacker).collect();
+
+    for (player, mut protection, children) in protected_query.iter_mut() {
+        let attacked = attackers.contains(&player);
+        if !protection.timer.tick(time.delta()).finished() && !attacked {
+            continue;
+        }
+        commands.entity(player).remove::<SpawnProtection>();
+        for &child in children.iter().filter(|child| bubble_query.contains(**child)) {
+            commands.entity(child).despawn_recursive();
+        }
+        notification_events.send(NotificationEvent::new(player, "Spawn protection ended", NotificationPriority::Low));
+    }
+}
+
+// HELPER FUNCTION
+
+/**
+Helper function to pick where a player on `team` spawns.
+Players with a team spawn in their team's zone if it has spawn points.
+Spawn points far from living enemies and out of their sight are preferred,
+and one of the best few is picked at random so spawns aren't predictable.
+*/
+pub fn choose_spawn_point(
+    context: &RapierContext,
+    rng: &mut GameRng,
+    spawn_points: &[(Transform, Option<Team>)],
+    team: Option<Team>,
+    living: &[(Vec3, Option<Team>)],
+) -> Transform {
+    // How many of the safest spawn points are picked from
+    const CANDIDATES: usize = 3;
+    // Being in sight of a player counts as being this much closer to them
+    const SIGHT_PENALTY: f32 = 0.25;
+    // Height of the eyes above a player's center
+    const EYE_HEIGHT: f32 = 1.5;
+
+    let has_zone = team.is_some() && spawn_points.iter().any(|(_, zone)| *zone == team);
+    let mut rated: Vec<(f32, Transform)> = spawn_points
+        .iter()
+        .filter(|(_, zone)| !has_zone || *zone == team)
+        .map(|(spawn, _)| {
+            let safety = living
+                .iter()
+                .filter(|(_, other)| !Team::are_allies(team.as_ref(), other.as_ref()))
+                .map(|(player, _)| {
+                    let distance = player.distance(spawn.translation);
+                    let eyes = *player + Vec3::Y * EYE_HEIGHT;
+                    if is_occluded(context, eyes, spawn.translation) {
+                        distance
+                    } else {
+                        distance * SIGHT_PENALTY
+                    }
+                })
+                .fold(f32::INFINITY, f32::min);
+            (safety, *spawn)
+        })
+        .collect();
+    if rated.is_empty() {
+        return Transform::from_translation(PLAYER_SPAWN_POSITION);
+    }
+
+    rated.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let candidates = rated.len().min(CANDIDATES);
+    let pick = ((rng.next_f32() * candidates as f32) as usize).min(candidates - 1);
+    rated[pick].1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// How many spawns every test picks, so the random choice among the safest points is covered
+    const SPAWNS: usize = 64;
+
+    fn spawn_point(x: f32, zone: Option<Team>) -> (Transform, Option<Team>) {
+        (Transform::from_xyz(x, 0.0, 0.0), zone)
+    }
+
+    /// Where a player on `team` spawns over many respawns
+    fn picks(spawn_points: &[(Transform, Option<Team>)], team: Option<Team>, living: &[(Vec3, Option<Team>)]) -> Vec<f32> {
+        let context = RapierContext::default();
+        let mut rng = GameRng::new(0x5eed);
+        (0..SPAWNS)
+            .map(|_| choose_spawn_point(&context, &mut rng, spawn_points, team, living).translation.x)
+            .collect()
+    }
+
+    fn zoned_arena() -> Vec<(Transform, Option<Team>)> {
+        vec![
+            spawn_point(-40.0, Some(Team::Red)),
+            spawn_point(-30.0, Some(Team::Red)),
+            spawn_point(30.0, Some(Team::Blue)),
+            spawn_point(40.0, Some(Team::Blue)),
+        ]
+    }
+
+    #[test]
+    fn players_spawn_in_their_team_zone() {
+        let arena = zoned_arena();
+
+        assert!(picks(&arena, Some(Team::Red), &[]).iter().all(|x| *x < 0.0));
+        assert!(picks(&arena, Some(Team::Blue), &[]).iter().all(|x| *x > 0.0));
+    }
+
+    #[test]
+    fn teams_without_spawn_points_spawn_anywhere() {
+        let arena = [spawn_point(-40.0, Some(Team::Red)), spawn_point(0.0, None), spawn_point(40.0, None)];
+
+        let picked = picks(&arena, Some(Team::Blue), &[]);
+
+        assert!(picked.contains(&-40.0));
+        assert!(picked.contains(&0.0));
+        assert!(picked.contains(&40.0));
+    }
+
+    #[test]
+    fn players_without_a_team_ignore_team_zones() {
+        let picked = picks(&zoned_arena(), None, &[]);
+
+        assert!(picked.iter().any(|x| *x < 0.0));
+        assert!(picked.iter().any(|x| *x > 0.0));
+    }
+
+    #[test]
+    fn spawn_points_next_to_enemies_are_avoided() {
+        let arena: Vec<_> = [0.0, 10.0, 20.0, 30.0].map(|x| spawn_point(x, None)).into();
+        // The ally doesn't make the point next to them any less safe
+        let living = [(Vec3::new(1.0, 0.0, 0.0), Some(Team::Blue)), (Vec3::new(29.0, 0.0, 0.0), Some(Team::Red))];
+
+        let picked = picks(&arena, Some(Team::Red), &living);
+
+        assert!(!picked.contains(&0.0));
+        assert!(picked.contains(&30.0));
+    }
+
+    #[test]
+    fn arenas_without_spawn_points_use_the_default_position() {
+        let context = RapierContext::default();
+        let mut rng = GameRng::new(1);
+
+        let spawn = choose_spawn_point(&context, &mut rng, &[], Some(Team::Red), &[]);
+
+        assert_eq!(spawn.translation, PLAYER_SPAWN_POSITION);
+    }
+}
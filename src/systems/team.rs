@@ -0,0 +1,107 @@
+use crate::components::player::Player;
+use crate::components::score::Score;
+use crate::components::team::Team;
+use crate::resources::game::MatchSettings;
+use crate::resources::rng::GameRng;
+use crate::resources::team::TeamAssets;
+use bevy::prelude::*;
+
+/// Puts players without a team on the smaller team when the mode has teams
+pub fn assign_team_system(
+    mut commands: Commands,
+    settings: Res<MatchSettings>,
+    mut rng: ResMut<GameRng>,
+    unassigned_query: Query<Entity, (With<Player>, Without<Team>)>,
+    team_query: Query<&Team, With<Player>>,
+) {
+    if !settings.mode.has_teams() || unassigned_query.is_empty() {
+        return;
+    }
+
+    let mut counts = team_counts(team_query.iter());
+    for player in unassigned_query.iter() {
+        let team = if counts[0] == counts[1] {
+            Team::ALL[(rng.next_f32() * 2.0) as usize % 2]
+        } else if counts[0] < counts[1] {
+            Team::Red
+        } else {
+            Team::Blue
+        };
+        counts[team_index(team)] += 1;
+        commands.entity(player).insert(team);
+    }
+}
+
+/**
+Evens out the teams before a match, moving players from the bigger team until they differ by at most one.
+Teams are removed when the mode has none.
+*/
+pub fn balance_teams_system(
+    mut commands: Commands,
+    settings: Res<MatchSettings>,
+    team_query: Query<(Entity, &Team), With<Player>>,
+) {
+    if !settings.mode.has_teams() {
+        for (player, _) in team_query.iter() {
+            commands.entity(player).remove::<Team>();
+        }
+        return;
+    }
+
+    let mut counts = team_counts(team_query.iter().map(|(_, team)| team));
+    for (player, team) in team_query.iter() {
+        let own = team_index(*team);
+        let other = 1 - own;
+        if counts[own] <= counts[other] + 1 {
+            continue;
+        }
+        counts[own] -= 1;
+        counts[other] += 1;
+        commands.entity(player).insert(Team::ALL[other]);
+    }
+}
+
+/// Draws players in the color of their team
+pub fn team_color_system(
+    team_assets: Res<TeamAssets>,
+    mut player_query: Query<(Option<&Team>, &mut MeshMaterial3d<StandardMaterial>), With<Player>>,
+) {
+    for (team, mut material) in player_query.iter_mut() {
+        let wanted = team_assets.material_for(team);
+        if material.0 != wanted {
+            material.0 = wanted;
+        }
+    }
+}
+
+// HELPER FUNCTION
+
+/// Helper function to find where a team is in [`Team::ALL`]
+fn team_index(team: Team) -> usize {
+    Team::ALL.iter().position(|other| *other == team).unwrap_or(0)
+}
+
+/// Helper function to add up the scores of each team's players, in the order of [`Team::ALL`]
+pub fn team_totals<'a>(scores: impl Iterator<Item = (Option<&'a Team>, &'a Score)>) -> [Score; 2] {
+    let mut totals = [Score::default(), Score::default()];
+    for (team, score) in scores {
+        let Some(team) = team else {
+            continue;
+        };
+        let total = &mut totals[team_index(*team)];
+        total.kills += score.kills;
+        total.deaths += score.deaths;
+        total.assists += score.assists;
+        total.objective += score.objective;
+    }
+    totals
+}
+
+/// Helper function to count the players on each team
+fn team_counts<'a>(teams: impl Iterator<Item = &'a Team>) -> [usize; 2] {
+    let mut counts = [0; 2];
+    for team in teams {
+        counts[team_index(*team)] += 1;
+    }
+    counts
+}
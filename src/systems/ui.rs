@@ -1,13 +1,50 @@
-use crate::components::player::{Direction, Inventory, Player};
+use crate::components::player::{
+    Direction, Inventory, ItemChannel, ItemCooldowns, LocalPlayer, Player, PlayerStats, Reloading,
+};
+use crate::components::camera::PlayerCamera;
+use crate::components::combat::Eliminated;
+use crate::components::objective::CaptureZone;
+use crate::components::score::Score;
+use crate::components::team::Team;
 use crate::components::ui::{
-    FpsText, Info, InfoText, InventorySlot, InventorySlotImage, WeaponDurabilityText, WeaponSlot,
-    WeaponSlotImage,
+    AmmoText, AnchorTarget, DamageIndicator, DamageIndicatorAnchor, DamageVignette, FloatingDamageNumber, FpsText,
+    HitMarker, HitMarkerDot, Info, InfoText, InventorySlot, KillFeed, KillFeedEntry, MatchOverlay, MatchOverlayText, MatchTimerText, NotificationList, SafeZoneText, CaptureBar, CaptureBarFill, CaptureText, Scoreboard, ScoreboardRows, Toast, InventorySlotCooldown, InventorySlotImage,
+    ItemChannelBar, ItemChannelBarFill, Nameplate, NameplateHealthFill, Vital, VitalBarFill, VitalText,
+    WeaponDurabilityText, WeaponSlot, WeaponSlotImage, WorldAnchor,
 };
-use bevy::color::palettes::tailwind::{GRAY_400, GRAY_500, GRAY_700, ORANGE_500, RED_400};
+use crate::components::world::{EntityName, OutsideZone};
+use crate::events::combat::{DamageTakenEvent, DeathEvent};
+use crate::events::notification::{NotificationEvent, NotificationPriority};
+use crate::resources::game::{DamageRules, GameMode, GameState, MatchClock, MatchSettings};
+use crate::resources::zone::SafeZone;
+use crate::systems::area::is_occluded;
+use crate::systems::objective::hill_color;
+use crate::systems::team::team_totals;
+use bevy::color::palettes::tailwind::{BLUE_300, GRAY_400, GRAY_500, GRAY_700, ORANGE_500, RED_400, RED_600};
 use bevy::diagnostic::{Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::image::*;
 use bevy::prelude::*;
 use bevy::render::camera::RenderTarget::Image;
+use bevy_rapier3d::prelude::*;
+
+/// Size in pixels of a damage indicator
+const DAMAGE_INDICATOR_SIZE: f32 = 14.0;
+/// Distance in pixels of damage indicators from the crosshair
+const DAMAGE_INDICATOR_RADIUS: f32 = 90.0;
+/// Seconds a damage indicator stays on screen
+const DAMAGE_INDICATOR_TIME: f32 = 1.5;
+/// Width in pixels of a nameplate
+const NAMEPLATE_WIDTH: f32 = 100.0;
+/// Width in pixels of the box a damage number is centered in
+const DAMAGE_NUMBER_WIDTH: f32 = 60.0;
+/// Most notifications shown at once
+const MAX_TOASTS: usize = 4;
+/// Most kills shown in the kill feed at once
+const MAX_KILL_FEED_ENTRIES: usize = 5;
+/// Seconds a kill stays in the kill feed
+const KILL_FEED_TIME: f32 = 6.0;
+/// Seconds before disappearing that notifications and kills start fading out
+const FADE_TIME: f32 = 0.5;
 
 // pub fn update_debug_info_system(
 //     mut fps_history: Local<VecDeque<f64>>,
@@ -26,7 +63,7 @@ use bevy::render::camera::RenderTarget::Image;
 
 pub fn update_player_info_system(
     mut info_text_query: Query<(&mut Text, &InfoText), With<InfoText>>,
-    player_query: Query<(&Transform, &Direction), With<Player>>,
+    player_query: Query<(&Transform, &Direction), With<LocalPlayer>>,
     diagnostics: Res<DiagnosticsStore>,
 ) {
     for (mut text, info_text) in info_text_query.iter_mut() {
@@ -84,16 +121,16 @@ fn set_text(text: &mut Text, value: String) {
 pub fn update_inventory_ui_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    player_query: Query<&Inventory, With<Player>>,
+    player_query: Query<&Inventory, With<LocalPlayer>>,
     mut item_slot_query: Query<(
         Entity,
         &mut BorderColor,
         &mut InventorySlot,
         Option<&Children>,
     )>,
-    mut weapon_slot_query: Query<(Entity, &mut WeaponSlot, Option<&Children>)>,
+    mut weapon_slot_query: Query<(Entity, &mut BorderColor, &WeaponSlot, Option<&Children>), Without<InventorySlot>>,
     mut inventory_slot_image_query: Query<Entity, With<InventorySlotImage>>,
-    mut weapon_slot_image_query: Query<Entity, With<WeaponSlotImage>>,
+    weapon_slot_image_query: Query<&WeaponSlotImage>,
 ) {
     if let Ok(inventory) = player_query.get_single() {
         for (slot_entity, mut border, slot_marker, item_slot_children_opt) in
@@ -148,11 +185,18 @@ pub fn update_inventory_ui_system(
                 }
             }
         }
-        for (weapon_slot_entity, weapon_slot, weapon_slot_opt_children) in
+        for (weapon_slot_entity, mut weapon_border, weapon_slot, weapon_slot_opt_children) in
             weapon_slot_query.iter_mut()
         {
+            // highlight the weapon that is currently held
+            if weapon_slot.slot == inventory.weapons.active {
+                weapon_border.0 = Color::from(BLUE_300);
+            } else {
+                weapon_border.0 = Color::WHITE;
+            }
+
             // update ui for weapon slot
-            if let Some(my_weapon) = &inventory.weapon {
+            if let Some(my_weapon) = inventory.weapons.get(weapon_slot.slot) {
                 let asset_path = match my_weapon.name.as_str() {
                     "Golok" => "textures/golok.png",
                     _ => "textures/default_item.png",
@@ -162,18 +206,23 @@ pub fn update_inventory_ui_system(
 
                 let image_handle = asset_server.load(asset_path);
 
-                // Check if the weapon slot has a child
+                // Check if the weapon slot already shows this weapon,
+                // a swapped weapon replaces the old image
                 let mut has_image = false;
                 if let Some(children) = weapon_slot_opt_children {
                     for &child in children.iter() {
-                        if weapon_slot_image_query.get(child).is_ok() {
-                            has_image = true;
-                            break;
+                        if let Ok(image) = weapon_slot_image_query.get(child) {
+                            if image.weapon == my_weapon.name {
+                                has_image = true;
+                            } else {
+                                commands.entity(child).despawn_recursive();
+                            }
                         }
                     }
                 }
 
                 if !has_image {
+                    let slot = weapon_slot.slot;
                     commands.entity(weapon_slot_entity).with_children(|parent| {
                         parent
                             .spawn(Node {
@@ -182,23 +231,25 @@ pub fn update_inventory_ui_system(
                                 ..default()
                             })
                             .insert(ImageNode::new(image_handle))
-                            .insert(WeaponSlotImage)
-                            .with_children(|slot| {
-                                slot.spawn((
+                            .insert(WeaponSlotImage {
+                                weapon: my_weapon.name.clone(),
+                            })
+                            .with_children(|slot_node| {
+                                slot_node.spawn((
                                     Node {
                                         left: Val::Px(0.0),
                                         bottom: Val::Px(0.0),
                                         position_type: PositionType::Absolute,
                                         ..default()
                                     },
-                                    Text::new(my_weapon.durability.to_string()),
+                                    Text::new(my_weapon.slot_counter().to_string()),
                                     TextColor(Color::from(ORANGE_500)),
                                     TextFont {
                                         font: asset_font,
                                         font_size: 12.0,
                                         ..default()
                                     },
-                                    WeaponDurabilityText,
+                                    WeaponDurabilityText { slot },
                                 ));
                             });
                     });
@@ -217,14 +268,939 @@ pub fn update_inventory_ui_system(
 }
 
 pub fn update_durability_text_system(
-    inventory_query: Query<&Inventory, With<Player>>,
-    mut text_query: Query<&mut Text, With<WeaponDurabilityText>>,
+    inventory_query: Query<&Inventory, With<LocalPlayer>>,
+    mut text_query: Query<(&mut Text, &WeaponDurabilityText)>,
 ) {
     if let Ok(inventory) = inventory_query.get_single() {
-        if let Some(weapon) = &inventory.weapon {
-            for mut text in text_query.iter_mut() {
-                text.0 = weapon.durability.to_string();
+        for (mut text, durability_text) in text_query.iter_mut() {
+            if let Some(weapon) = inventory.weapons.get(durability_text.slot) {
+                text.0 = weapon.slot_counter().to_string();
+            }
+        }
+    }
+}
+
+/// Updates the item channel bar and the cooldown overlay of each inventory slot
+pub fn update_item_progress_ui_system(
+    player_query: Query<(&Inventory, &ItemCooldowns, Option<&ItemChannel>), With<LocalPlayer>>,
+    mut channel_bar_query: Query<&mut Node, (With<ItemChannelBar>, Without<ItemChannelBarFill>, Without<InventorySlotCooldown>)>,
+    mut channel_fill_query: Query<&mut Node, (With<ItemChannelBarFill>, Without<ItemChannelBar>, Without<InventorySlotCooldown>)>,
+    mut cooldown_query: Query<(&mut Node, &InventorySlotCooldown), (Without<ItemChannelBar>, Without<ItemChannelBarFill>)>,
+) {
+    let Ok((inventory, cooldowns, channel)) = player_query.get_single() else {
+        return;
+    };
+
+    for mut bar in channel_bar_query.iter_mut() {
+        bar.display = if channel.is_some() { Display::Flex } else { Display::None };
+    }
+    if let Some(channel) = channel {
+        for mut fill in channel_fill_query.iter_mut() {
+            fill.width = Val::Percent(channel.timer.fraction() * 100.);
+        }
+    }
+
+    for (mut overlay, slot_cooldown) in cooldown_query.iter_mut() {
+        let remaining = inventory
+            .slots
+            .get(slot_cooldown.slot)
+            .and_then(Option::as_ref)
+            .map(|item| cooldowns.remaining_fraction(item))
+            .unwrap_or(0.0);
+        overlay.height = Val::Percent(remaining * 100.);
+    }
+}
+
+/// Shows the magazine and reserve ammo of the held ranged weapon
+pub fn update_ammo_text_system(
+    player_query: Query<(&Inventory, Option<&Reloading>), With<LocalPlayer>>,
+    mut text_query: Query<&mut Text, With<AmmoText>>,
+) {
+    let Ok((inventory, reloading)) = player_query.get_single() else {
+        return;
+    };
+    let ranged = inventory
+        .weapons
+        .active_weapon()
+        .and_then(|weapon| weapon.ranged.as_ref());
+
+    for mut text in text_query.iter_mut() {
+        text.0 = match (ranged, reloading) {
+            (Some(_), Some(_)) => String::from("Reloading..."),
+            (Some(ranged), None) => format!("{} / {}", ranged.magazine, ranged.reserve_ammo),
+            (None, _) => String::new(),
+        };
+    }
+}
+
+/// Updates the health and armor bars when the player's stats change
+pub fn update_vitals_ui_system(
+    player_query: Query<&PlayerStats, (With<LocalPlayer>, Changed<PlayerStats>)>,
+    mut fill_query: Query<(&mut Node, &VitalBarFill)>,
+    mut text_query: Query<(&mut Text, &VitalText)>,
+) {
+    let Ok(player_stats) = player_query.get_single() else {
+        return;
+    };
+    let value_of = |vital: Vital| match vital {
+        Vital::Health => (player_stats.health, player_stats.max_health),
+        Vital::Armor => (player_stats.armor, player_stats.max_armor),
+    };
+
+    for (mut fill, bar) in fill_query.iter_mut() {
+        let (value, max) = value_of(bar.vital);
+        let fraction = if max > 0.0 { (value / max).clamp(0.0, 1.0) } else { 0.0 };
+        fill.width = Val::Percent(fraction * 100.);
+    }
+    for (mut text, vital_text) in text_query.iter_mut() {
+        let (value, max) = value_of(vital_text.vital);
+        text.0 = format!("{:.0} / {:.0}", value.ceil(), max);
+    }
+}
+
+/// Keeps the screen edge red while the local player's health is low and flashes it when they are hurt
+pub fn damage_vignette_system(
+    time: Res<Time>,
+    mut taken_events: EventReader<DamageTakenEvent>,
+    player_query: Query<(Entity, Ref<PlayerStats>), With<LocalPlayer>>,
+    mut vignette_query: Query<(&mut BorderColor, &mut DamageVignette)>,
+) {
+    // Below this fraction of max health the screen edge starts to glow
+    const LOW_HEALTH: f32 = 0.3;
+    // How much of the flash fades each second
+    const FLASH_FADE: f32 = 2.0;
+    const MAX_ALPHA: f32 = 0.6;
+
+    let Ok((player, player_stats)) = player_query.get_single() else {
+        return;
+    };
+    let Ok((mut border, mut vignette)) = vignette_query.get_single_mut() else {
+        return;
+    };
+
+    if player_stats.is_changed() {
+        let fraction = if player_stats.max_health > 0.0 {
+            player_stats.health / player_stats.max_health
+        } else {
+            0.0
+        };
+        vignette.low_health = (1.0 - fraction / LOW_HEALTH).clamp(0.0, 1.0);
+    }
+    for event in taken_events.read() {
+        if event.target == player && event.amount > 0.0 {
+            vignette.flash = 1.0;
+        }
+    }
+    if vignette.flash <= 0.0 && !player_stats.is_changed() {
+        return;
+    }
+
+    vignette.flash = (vignette.flash - FLASH_FADE * time.delta_secs()).max(0.0);
+    let strength = vignette.low_health.max(vignette.flash);
+    border.0 = Color::from(RED_600.with_alpha(strength * MAX_ALPHA));
+}
+
+/// Shows a mark around the crosshair pointing towards whoever hurt the local player
+pub fn spawn_damage_indicator_system(
+    mut commands: Commands,
+    mut taken_events: EventReader<DamageTakenEvent>,
+    player_query: Query<(Entity, &Transform, &Direction), With<LocalPlayer>>,
+    anchor_query: Query<Entity, With<DamageIndicatorAnchor>>,
+    mut indicator_query: Query<&mut DamageIndicator>,
+) {
+    let Ok((player, transform, direction)) = player_query.get_single() else {
+        return;
+    };
+    let Ok(anchor) = anchor_query.get_single() else {
+        return;
+    };
+
+    for event in taken_events.read() {
+        if event.target != player {
+            continue;
+        }
+        // Self inflicted damage points at where it happened instead
+        let attacker = event
+            .source
+            .instigator
+            .as_ref()
+            .map(|instigator| instigator.entity)
+            .filter(|attacker| *attacker != player);
+
+        // The same attacker refreshes their indicator instead of stacking another one
+        if let Some(mut indicator) = indicator_query
+            .iter_mut()
+            .find(|indicator| attacker.is_some() && indicator.attacker == attacker)
+        {
+            indicator.source = event.origin;
+            indicator.timer.reset();
+            continue;
+        }
+
+        let mut node = Node {
+            width: Val::Px(DAMAGE_INDICATOR_SIZE),
+            height: Val::Px(DAMAGE_INDICATOR_SIZE),
+            position_type: PositionType::Absolute,
+            ..default()
+        };
+        place_damage_indicator(&mut node, transform, direction, event.origin);
+        commands.entity(anchor).with_children(|parent| {
+            parent.spawn((
+                node,
+                BackgroundColor(Color::from(RED_600)),
+                DamageIndicator {
+                    attacker,
+                    source: event.origin,
+                    timer: Timer::from_seconds(DAMAGE_INDICATOR_TIME, TimerMode::Once),
+                },
+            ));
+        });
+    }
+}
+
+/// Keeps damage indicators pointing at their attacker as the player turns and fades them out
+pub fn update_damage_indicator_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    player_query: Query<(&Transform, &Direction), With<LocalPlayer>>,
+    attacker_query: Query<&Transform, With<Player>>,
+    mut indicator_query: Query<(Entity, &mut DamageIndicator, &mut Node, &mut BackgroundColor)>,
+) {
+    let Ok((transform, direction)) = player_query.get_single() else {
+        return;
+    };
+
+    for (entity, mut indicator, mut node, mut color) in indicator_query.iter_mut() {
+        if indicator.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        if let Some(attacker_transform) = indicator.attacker.and_then(|attacker| attacker_query.get(attacker).ok()) {
+            indicator.source = attacker_transform.translation;
+        }
+        place_damage_indicator(&mut node, transform, direction, indicator.source);
+        color.0 = Color::from(RED_600.with_alpha(indicator.timer.fraction_remaining()));
+    }
+}
+
+/// Shows the hit marker when the local player's attacks land, in red and wider for a kill
+pub fn hit_marker_system(
+    time: Res<Time>,
+    mut taken_events: EventReader<DamageTakenEvent>,
+    player_query: Query<Entity, With<LocalPlayer>>,
+    mut marker_query: Query<(&mut HitMarker, &mut Node, &mut Visibility)>,
+    mut dot_query: Query<&mut BackgroundColor, With<HitMarkerDot>>,
+) {
+    const HIT_TIME: f32 = 0.2;
+    const KILL_TIME: f32 = 0.6;
+
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+    let Ok((mut marker, mut node, mut visibility)) = marker_query.get_single_mut() else {
+        return;
+    };
+
+    for event in taken_events.read() {
+        if event.amount <= 0.0 || event.target == player || !event.source.is_from(player) {
+            continue;
+        }
+        // A kill marker isn't cut short by another hit
+        if marker.kill && !marker.timer.finished() && !event.lethal {
+            continue;
+        }
+        marker.kill = event.lethal;
+        marker.timer = Timer::from_seconds(if event.lethal { KILL_TIME } else { HIT_TIME }, TimerMode::Once);
+
+        let (size, color) = if event.lethal { (48., Color::from(RED_600)) } else { (36., Color::WHITE) };
+        node.width = Val::Px(size);
+        node.height = Val::Px(size);
+        for mut dot in dot_query.iter_mut() {
+            dot.0 = color;
+        }
+        *visibility = Visibility::Inherited;
+    }
+
+    if marker.timer.tick(time.delta()).just_finished() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Gives every other player a nameplate and removes nameplates of players that are gone
+pub fn spawn_nameplate_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    new_player_query: Query<(Entity, &EntityName), (Added<Player>, Without<LocalPlayer>)>,
+    player_query: Query<(), With<Player>>,
+    nameplate_query: Query<(Entity, &Nameplate)>,
+) {
+    // How far away other players can be seen, in world units
+    const NAMEPLATE_DISTANCE: f32 = 40.0;
+
+    for (nameplate, plate) in nameplate_query.iter() {
+        if !player_query.contains(plate.player) {
+            commands.entity(nameplate).despawn_recursive();
+        }
+    }
+
+    for (player, name) in new_player_query.iter() {
+        commands
+            .spawn((
+                Node {
+                    width: Val::Px(NAMEPLATE_WIDTH),
+                    position_type: PositionType::Absolute,
+                    // Center the plate over the anchor point
+                    margin: UiRect::left(Val::Px(-NAMEPLATE_WIDTH / 2.0)),
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(2.),
+                    ..default()
+                },
+                Visibility::Hidden,
+                WorldAnchor {
+                    target: AnchorTarget::Entity(player),
+                    offset: Vec3::Y,
+                    max_distance: NAMEPLATE_DISTANCE,
+                    occludable: true,
+                },
+                Nameplate { player },
+            ))
+            .with_children(|plate| {
+                plate.spawn((
+                    Text::new(name.name()),
+                    TextFont {
+                        font: asset_server.load("fonts/OpenSans.ttf"),
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+                plate
+                    .spawn((
+                        Node {
+                            width: Val::Percent(100.),
+                            height: Val::Px(5.),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.5)),
+                    ))
+                    .with_children(|bar| {
+                        bar.spawn((
+                            Node {
+                                width: Val::Percent(100.),
+                                height: Val::Percent(100.),
+                                ..default()
+                            },
+                            BackgroundColor(Color::from(RED_600)),
+                            NameplateHealthFill { player },
+                        ));
+                    });
+            });
+    }
+}
+
+/// Updates nameplate health bars when the health of their player changes
+pub fn update_nameplate_health_system(
+    player_query: Query<&PlayerStats, (With<Player>, Changed<PlayerStats>)>,
+    mut fill_query: Query<(&mut Node, &NameplateHealthFill)>,
+) {
+    for (mut fill, health_fill) in fill_query.iter_mut() {
+        let Ok(player_stats) = player_query.get(health_fill.player) else {
+            continue;
+        };
+        let fraction = if player_stats.max_health > 0.0 {
+            (player_stats.health / player_stats.max_health).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        fill.width = Val::Percent(fraction * 100.);
+    }
+}
+
+/// Pops up the damage the local player dealt where it landed
+pub fn spawn_damage_number_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut taken_events: EventReader<DamageTakenEvent>,
+    player_query: Query<Entity, With<LocalPlayer>>,
+) {
+    // How far away damage numbers are still shown, in world units
+    const DAMAGE_NUMBER_DISTANCE: f32 = 60.0;
+
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+
+    for event in taken_events.read() {
+        if event.amount <= 0.0 || event.target == player || !event.source.is_from(player) {
+            continue;
+        }
+        let color = if event.lethal { Color::from(RED_600) } else { Color::WHITE };
+        commands
+            .spawn((
+                Node {
+                    width: Val::Px(DAMAGE_NUMBER_WIDTH),
+                    position_type: PositionType::Absolute,
+                    margin: UiRect::left(Val::Px(-DAMAGE_NUMBER_WIDTH / 2.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                Visibility::Hidden,
+                WorldAnchor {
+                    target: AnchorTarget::Point(event.point),
+                    offset: Vec3::ZERO,
+                    max_distance: DAMAGE_NUMBER_DISTANCE,
+                    occludable: false,
+                },
+                FloatingDamageNumber {
+                    timer: Timer::from_seconds(0.8, TimerMode::Once),
+                },
+            ))
+            .with_children(|number| {
+                number.spawn((
+                    Text::new(format!("{:.0}", event.amount.ceil())),
+                    TextFont {
+                        font: asset_server.load("fonts/OpenSans.ttf"),
+                        font_size: if event.lethal { 22.0 } else { 18.0 },
+                        ..default()
+                    },
+                    TextColor(color),
+                ));
+            });
+    }
+}
+
+/// Makes damage numbers rise and fade, removing them once they are gone
+pub fn update_damage_number_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut number_query: Query<(Entity, &mut FloatingDamageNumber, &mut WorldAnchor, &Children)>,
+    mut text_query: Query<&mut TextColor>,
+) {
+    // World units a damage number rises each second
+    const RISE_SPEED: f32 = 1.5;
+
+    for (entity, mut number, mut anchor, children) in number_query.iter_mut() {
+        if number.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        anchor.offset.y += RISE_SPEED * time.delta_secs();
+        let alpha = number.timer.fraction_remaining();
+        for &child in children.iter() {
+            if let Ok(mut color) = text_query.get_mut(child) {
+                color.0.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+/// Moves world anchored UI nodes to where their target is on screen, hiding the ones that can't be seen
+pub fn world_anchor_system(
+    rapier_context: ReadDefaultRapierContext,
+    camera_query: Query<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    target_query: Query<&GlobalTransform, Without<WorldAnchor>>,
+    mut anchor_query: Query<(&WorldAnchor, &mut Node, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+    let camera_position = camera_transform.translation();
+
+    for (anchor, mut node, mut visibility) in anchor_query.iter_mut() {
+        let target_position = match anchor.target {
+            AnchorTarget::Entity(entity) => target_query.get(entity).ok().map(GlobalTransform::translation),
+            AnchorTarget::Point(point) => Some(point),
+        };
+        let screen_position = target_position
+            .map(|position| position + anchor.offset)
+            .filter(|position| position.distance(camera_position) <= anchor.max_distance)
+            .filter(|position| !anchor.occludable || !is_occluded(rapier_context.single(), camera_position, *position))
+            .and_then(|position| camera.world_to_viewport(camera_transform, position).ok());
+
+        let Some(screen_position) = screen_position else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        node.left = Val::Px(screen_position.x);
+        node.top = Val::Px(screen_position.y);
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+/**
+Shows notifications sent to the local player and removes them once their time is up.
+A message that is already shown is stacked onto it instead of shown twice,
+and when there are too many the least important and oldest ones are dropped.
+*/
+pub fn notification_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut notification_events: EventReader<NotificationEvent>,
+    player_query: Query<Entity, With<LocalPlayer>>,
+    list_query: Query<Entity, With<NotificationList>>,
+    mut toast_query: Query<(Entity, &mut Toast, &mut Text, &mut TextColor)>,
+) {
+    let (Ok(player), Ok(list)) = (player_query.get_single(), list_query.get_single()) else {
+        return;
+    };
+
+    let mut expired = Vec::new();
+    for (entity, mut toast, _, mut color) in toast_query.iter_mut() {
+        if toast.timer.tick(time.delta()).finished() {
+            expired.push(entity);
+        } else {
+            color.0.set_alpha(fade_alpha(&toast.timer));
+        }
+    }
+
+    // Messages sent this frame that are not on screen yet
+    let mut pending: Vec<Toast> = Vec::new();
+    for event in notification_events.read() {
+        if event.player != player {
+            continue;
+        }
+        let existing = toast_query
+            .iter_mut()
+            .find(|(entity, toast, ..)| !expired.contains(entity) && toast.message == event.message);
+        if let Some((_, mut toast, mut text, mut color)) = existing {
+            stack_toast(&mut toast, event.priority);
+            text.0 = toast_text(&toast);
+            color.0.set_alpha(1.0);
+        } else if let Some(toast) = pending.iter_mut().find(|toast| toast.message == event.message) {
+            stack_toast(toast, event.priority);
+        } else {
+            pending.push(Toast {
+                message: event.message.clone(),
+                priority: event.priority,
+                count: 1,
+                timer: Timer::from_seconds(event.priority.lifetime(), TimerMode::Once),
+            });
+        }
+    }
+
+    // Keep the most important notifications, newer ones first among equals
+    let mut shown: Vec<(Option<Entity>, NotificationPriority, f32)> = toast_query
+        .iter()
+        .filter(|(entity, ..)| !expired.contains(entity))
+        .map(|(entity, toast, ..)| (Some(entity), toast.priority, toast.timer.elapsed_secs()))
+        .chain(pending.iter().map(|toast| (None, toast.priority, 0.0)))
+        .collect();
+    shown.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.total_cmp(&b.2)));
+    let dropped_pending = shown
+        .iter()
+        .skip(MAX_TOASTS)
+        .filter(|(entity, ..)| entity.is_none())
+        .count();
+    expired.extend(shown.iter().skip(MAX_TOASTS).filter_map(|(entity, ..)| *entity));
+    // Pending notifications are all the newest, so the least important ones are dropped first
+    pending.sort_by(|a, b| b.priority.cmp(&a.priority));
+    pending.truncate(pending.len() - dropped_pending);
+
+    for entity in expired {
+        commands.entity(entity).despawn_recursive();
+    }
+    for toast in pending {
+        let color = match toast.priority {
+            NotificationPriority::Low => Color::from(GRAY_400),
+            NotificationPriority::Normal => Color::WHITE,
+            NotificationPriority::High => Color::from(ORANGE_500),
+        };
+        commands.entity(list).with_children(|parent| {
+            parent.spawn((
+                Node {
+                    padding: UiRect::axes(Val::Px(8.), Val::Px(2.)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0., 0., 0., 0.5)),
+                Text::new(toast_text(&toast)),
+                TextFont {
+                    font: asset_server.load("fonts/OpenSans.ttf"),
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(color),
+                toast,
+            ));
+        });
+    }
+}
+
+/// Adds kills to the kill feed as "killer [cause] victim", highlighting the ones the local player was part of
+pub fn kill_feed_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut death_events: EventReader<DeathEvent>,
+    player_query: Query<Entity, With<LocalPlayer>>,
+    feed_query: Query<Entity, With<KillFeed>>,
+    mut entry_query: Query<(Entity, &mut KillFeedEntry, &mut TextColor)>,
+) {
+    let Ok(feed) = feed_query.get_single() else {
+        return;
+    };
+    let player = player_query.get_single().ok();
+
+    let mut entries = Vec::new();
+    for (entity, mut entry, mut color) in entry_query.iter_mut() {
+        if entry.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            color.0.set_alpha(fade_alpha(&entry.timer));
+            entries.push((entity, entry.timer.elapsed_secs()));
+        }
+    }
+
+    for event in death_events.read() {
+        let text = match event.source.instigator_name() {
+            Some(killer) if !event.source.is_from(event.victim) => {
+                format!("{} [{}] {}", killer, event.source.cause, event.victim_name)
+            }
+            _ => format!("[{}] {}", event.source.cause, event.victim_name),
+        };
+        let involves_player =
+            player.is_some_and(|player| event.victim == player || event.source.is_from(player));
+        let background = if involves_player {
+            Color::srgba(0.6, 0.1, 0.1, 0.6)
+        } else {
+            Color::srgba(0., 0., 0., 0.5)
+        };
+
+        // Make room by removing the oldest kills
+        entries.sort_by(|a, b| b.1.total_cmp(&a.1));
+        while entries.len() >= MAX_KILL_FEED_ENTRIES {
+            let (oldest, _) = entries.remove(0);
+            commands.entity(oldest).despawn_recursive();
+        }
+
+        let entry = commands
+            .spawn((
+                Node {
+                    padding: UiRect::axes(Val::Px(8.), Val::Px(2.)),
+                    ..default()
+                },
+                BackgroundColor(background),
+                Text::new(text),
+                TextFont {
+                    font: asset_server.load("fonts/OpenSans.ttf"),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                KillFeedEntry {
+                    timer: Timer::from_seconds(KILL_FEED_TIME, TimerMode::Once),
+                },
+            ))
+            .id();
+        commands.entity(feed).add_child(entry);
+        entries.push((entry, 0.0));
+    }
+}
+
+/// Shows the scoreboard while Tab is held, listing every player sorted by score
+pub fn scoreboard_system(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    settings: Res<MatchSettings>,
+    player_query: Query<(Entity, &EntityName, Ref<Score>, Option<&Team>, Has<LocalPlayer>), With<Player>>,
+    mut scoreboard_query: Query<&mut Node, With<Scoreboard>>,
+    rows_query: Query<Entity, With<ScoreboardRows>>,
+) {
+    let Ok(mut scoreboard) = scoreboard_query.get_single_mut() else {
+        return;
+    };
+    let shown = keyboard_input.pressed(KeyCode::Tab);
+    scoreboard.display = if shown { Display::Flex } else { Display::None };
+
+    // Only rebuild the rows when opened or when a score changes while open
+    let changed = player_query.iter().any(|(_, _, score, ..)| score.is_changed());
+    if !shown || !(keyboard_input.just_pressed(KeyCode::Tab) || changed) {
+        return;
+    }
+    let Ok(rows) = rows_query.get_single() else {
+        return;
+    };
+
+    // In team modes players are grouped under their team, in the order of `Team::ALL`
+    let has_teams = settings.mode.has_teams();
+    let team_order = |team: Option<&Team>| match team {
+        Some(team) if has_teams => Team::ALL.iter().position(|other| other == team).unwrap_or(0),
+        _ => Team::ALL.len(),
+    };
+    let mut players: Vec<_> = player_query.iter().collect();
+    players.sort_by(|(entity_a, _, a, team_a, _), (entity_b, _, b, team_b, _)| {
+        team_order(*team_a)
+            .cmp(&team_order(*team_b))
+            .then(b.points().cmp(&a.points()))
+            .then(b.kills.cmp(&a.kills))
+            .then(a.deaths.cmp(&b.deaths))
+            .then(entity_a.cmp(entity_b))
+    });
+    let totals = team_totals(player_query.iter().map(|(_, _, score, team, _)| (team, score.into_inner())));
+
+    let font = asset_server.load("fonts/OpenSans.ttf");
+    commands.entity(rows).despawn_descendants();
+    commands.entity(rows).with_children(|parent| {
+        let mut current_group = None;
+        for (_, name, score, team, is_local) in players {
+            let group = team_order(team);
+            if has_teams && current_group != Some(group) {
+                current_group = Some(group);
+                if let Some(team) = Team::ALL.get(group) {
+                    let total = &totals[group];
+                    let cells = [
+                        format!("{} team", team.name()),
+                        total.points().to_string(),
+                        total.kills.to_string(),
+                        total.deaths.to_string(),
+                        total.assists.to_string(),
+                        String::new(),
+                    ];
+                    spawn_scoreboard_row(parent, &font, cells, team.color());
+                }
+            }
+
+            let color = if is_local { Color::from(ORANGE_500) } else { Color::WHITE };
+            let cells = [
+                name.name().to_string(),
+                score.points().to_string(),
+                score.kills.to_string(),
+                score.deaths.to_string(),
+                score.assists.to_string(),
+                // No latency to show until players can join over the network
+                String::from("-"),
+            ];
+            spawn_scoreboard_row(parent, &font, cells, color);
+        }
+    });
+}
+
+/// Shows the match clock during warmup and the match, and the overlay for the menu, lobby and summary
+pub fn update_match_ui_system(
+    state: Res<State<GameState>>,
+    clock: Res<MatchClock>,
+    (settings, rules): (Res<MatchSettings>, Res<DamageRules>),
+    player_query: Query<(&EntityName, &Score, Option<&Team>, Has<Eliminated>), With<Player>>,
+    mut timer_text_query: Query<&mut Text, (With<MatchTimerText>, Without<MatchOverlayText>)>,
+    mut overlay_query: Query<&mut Node, With<MatchOverlay>>,
+    mut overlay_text_query: Query<&mut Text, (With<MatchOverlayText>, Without<MatchTimerText>)>,
+) {
+    let remaining = clock.timer.remaining_secs().ceil() as u32;
+    let clock_text = match state.get() {
+        GameState::Warmup => format!("Warmup {}", remaining),
+        GameState::InProgress if settings.mode.has_teams() => {
+            let [red, blue] = team_totals(player_query.iter().map(|(_, score, team, _)| (team, score)));
+            format!(
+                "{}:{:02}  -  {} {} : {} {}  -  first team to {} kills",
+                remaining / 60,
+                remaining % 60,
+                Team::Red.name(),
+                red.kills,
+                blue.kills,
+                Team::Blue.name(),
+                settings.frag_limit
+            )
+        }
+        GameState::InProgress if settings.mode == GameMode::LastStanding => format!(
+            "{}:{:02}  -  {} players left",
+            remaining / 60,
+            remaining % 60,
+            player_query.iter().filter(|(.., eliminated)| !eliminated).count()
+        ),
+        GameState::InProgress if settings.mode == GameMode::KingOfTheHill => format!(
+            "{}:{:02}  -  first to hold the hill for {}s",
+            remaining / 60,
+            remaining % 60,
+            settings.hold_limit
+        ),
+        GameState::InProgress => format!(
+            "{}:{:02}  -  first to {} kills",
+            remaining / 60,
+            remaining % 60,
+            settings.frag_limit
+        ),
+        _ => String::new(),
+    };
+    for mut text in timer_text_query.iter_mut() {
+        text.0.clone_from(&clock_text);
+    }
+
+    let overlay_text = match state.get() {
+        GameState::MainMenu => Some(String::from("Press Enter to play")),
+        GameState::Lobby => Some(format!(
+            "Lobby: {} / {} players\n{}, friendly fire {}\nPress Enter to start the warmup, M to change the mode, F to toggle friendly fire, F5 to reload the match rules",
+            player_query.iter().count(),
+            settings.min_players,
+            settings.mode.name(),
+            if rules.friendly_fire { "on" } else { "off" }
+        )),
+        GameState::PostMatch => {
+            let mut standings: Vec<_> = player_query.iter().map(|(name, score, ..)| (name, score)).collect();
+            standings.sort_by(|(_, a), (_, b)| b.points().cmp(&a.points()));
+            let mut summary = match &clock.winner {
+                Some(winner) => format!("{} wins the match!\n\n", winner),
+                None => String::from("The match is a draw!\n\n"),
+            };
+            for (place, (name, score)) in standings.iter().enumerate() {
+                summary.push_str(&format!(
+                    "{}. {}  {} points  ({} / {} / {})\n",
+                    place + 1,
+                    name.name(),
+                    score.points(),
+                    score.kills,
+                    score.deaths,
+                    score.assists
+                ));
+            }
+            summary.push_str(&format!("\nNext match in {}", remaining));
+            Some(summary)
+        }
+        GameState::Warmup | GameState::InProgress => None,
+    };
+    for mut overlay in overlay_query.iter_mut() {
+        overlay.display = if overlay_text.is_some() { Display::Flex } else { Display::None };
+    }
+    if let Some(overlay_text) = overlay_text {
+        for mut text in overlay_text_query.iter_mut() {
+            text.0.clone_from(&overlay_text);
+        }
+    }
+}
+
+/// Counts down to the next shrink of the safe zone, and warns the local player when they are outside it
+pub fn update_zone_ui_system(
+    zone: Res<SafeZone>,
+    player_query: Query<Has<OutsideZone>, With<LocalPlayer>>,
+    mut text_query: Query<(&mut Text, &mut TextColor), With<SafeZoneText>>,
+) {
+    let outside = player_query.iter().any(|outside| outside);
+    let remaining = zone.timer.remaining_secs().ceil() as u32;
+    let mut zone_text = if !zone.active {
+        String::new()
+    } else if zone.is_closed() {
+        String::from("The zone has closed")
+    } else if zone.shrinking {
+        format!("Zone shrinking  {}", remaining)
+    } else {
+        format!("Zone shrinks in {}", remaining)
+    };
+    if zone.active && outside {
+        zone_text.push_str("  -  outside the zone!");
+    }
+    let color = if zone.active && outside { Color::from(RED_400) } else { Color::WHITE };
+
+    for (mut text, mut text_color) in text_query.iter_mut() {
+        if text.0 != zone_text {
+            text.0.clone_from(&zone_text);
+        }
+        if text_color.0 != color {
+            text_color.0 = color;
+        }
+    }
+}
+
+/// Shows who holds the hill, with their hold time or the capture progress on the bar
+pub fn update_capture_ui_system(
+    settings: Res<MatchSettings>,
+    zone_query: Query<&CaptureZone>,
+    local_query: Query<Entity, With<LocalPlayer>>,
+    player_query: Query<(&EntityName, &Score), With<Player>>,
+    mut bar_query: Query<&mut Node, (With<CaptureBar>, Without<CaptureBarFill>)>,
+    mut fill_query: Query<(&mut Node, &mut BackgroundColor), With<CaptureBarFill>>,
+    mut text_query: Query<&mut Text, With<CaptureText>>,
+) {
+    let zone = zone_query.iter().find(|zone| zone.active);
+    for mut bar in bar_query.iter_mut() {
+        bar.display = if zone.is_some() { Display::Flex } else { Display::None };
+    }
+
+    let (hill_text, fill) = match zone {
+        None => (String::new(), 0.0),
+        Some(zone) => {
+            let name_of = |player: Option<Entity>| {
+                player
+                    .and_then(|player| player_query.get(player).ok())
+                    .map(|(name, _)| name.name().to_string())
+                    .unwrap_or_default()
+            };
+            let held = zone
+                .owner
+                .and_then(|owner| player_query.get(owner).ok())
+                .map(|(_, score)| score.objective as f32 / settings.hold_limit.max(1) as f32)
+                .unwrap_or(0.0);
+            if zone.contested {
+                (String::from("The hill is contested!"), held)
+            } else if zone.capturer.is_some() {
+                (format!("{} is capturing the hill", name_of(zone.capturer)), zone.progress)
+            } else if zone.owner.is_some() {
+                (format!("{} holds the hill", name_of(zone.owner)), held)
+            } else {
+                (String::from("The hill is free"), 0.0)
             }
         }
+    };
+    let color = zone.map(|zone| hill_color(zone, local_query.get_single().ok())).unwrap_or(Color::WHITE);
+
+    for (mut node, mut background) in fill_query.iter_mut() {
+        node.width = Val::Percent(fill.clamp(0.0, 1.0) * 100.);
+        background.0 = color;
+    }
+    for mut text in text_query.iter_mut() {
+        if text.0 != hill_text {
+            text.0.clone_from(&hill_text);
+        }
     }
 }
+
+// HELPER FUNCTION
+
+/// Helper function to add one row of cells to the scoreboard grid
+fn spawn_scoreboard_row(parent: &mut ChildBuilder, font: &Handle<Font>, cells: [String; 6], color: Color) {
+    for cell in cells {
+        parent.spawn((
+            Text::new(cell),
+            TextFont {
+                font: font.clone(),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(color),
+        ));
+    }
+}
+
+/// Helper function to add a repeated message onto a notification that is already shown
+fn stack_toast(toast: &mut Toast, priority: NotificationPriority) {
+    toast.count += 1;
+    toast.priority = toast.priority.max(priority);
+    toast.timer = Timer::from_seconds(toast.priority.lifetime(), TimerMode::Once);
+}
+
+/// Helper function to build the text of a notification, with how often it was sent
+fn toast_text(toast: &Toast) -> String {
+    if toast.count > 1 {
+        format!("{} (x{})", toast.message, toast.count)
+    } else {
+        toast.message.clone()
+    }
+}
+
+/// Helper function to fade something out during the last moments of its timer
+fn fade_alpha(timer: &Timer) -> f32 {
+    (timer.remaining_secs() / FADE_TIME).min(1.0)
+}
+
+/// Helper function to place a damage indicator on the circle around the crosshair towards `source`
+fn place_damage_indicator(node: &mut Node, transform: &Transform, direction: &Direction, source: Vec3) {
+    let forward = direction.direction.with_y(0.0).normalize_or(Vec3::NEG_Z);
+    let right = forward.cross(Vec3::Y);
+    let to_source = (source - transform.translation).with_y(0.0);
+    // Ahead of the player is the top of the screen, where y is negative
+    let offset = Vec2::new(to_source.dot(right), -to_source.dot(forward)).normalize_or(Vec2::NEG_Y);
+    let position = offset * DAMAGE_INDICATOR_RADIUS - DAMAGE_INDICATOR_SIZE / 2.0;
+    node.left = Val::Px(position.x);
+    node.top = Val::Px(position.y);
+}
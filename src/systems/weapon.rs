@@ -0,0 +1,176 @@
+use crate::components::combat::{DamageSource, Instigator, Respawning, Stunned, ThrowParams};
+use crate::components::player::*;
+use crate::components::world::EntityName;
+use crate::events::combat::{AttackEvent, DamageEvent};
+use crate::resources::item::ItemAssets;
+use crate::resources::rng::GameRng;
+use crate::spawns::projectile::spawn_thrown_object;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Counts down the fire cooldown of every ranged weapon
+pub fn weapon_cooldown_system(
+    time: Res<Time>,
+    mut inventory_query: Query<&mut Inventory, With<Player>>,
+) {
+    for mut inventory in inventory_query.iter_mut() {
+        let cooling_down = inventory
+            .weapons
+            .weapons()
+            .any(|weapon| weapon.ranged.as_ref().is_some_and(|ranged| ranged.fire_cooldown > 0.0));
+        if !cooling_down {
+            continue;
+        }
+        // A ticking cooldown isn't a change to the inventory, systems watching Changed<Inventory> would run every frame
+        for weapon in inventory.bypass_change_detection().weapons.weapons_mut() {
+            if let Some(ranged) = weapon.ranged.as_mut().filter(|ranged| ranged.fire_cooldown > 0.0) {
+                ranged.fire_cooldown = (ranged.fire_cooldown - time.delta_secs()).max(0.0);
+            }
+        }
+    }
+}
+
+/**
+Fires the held ranged weapon while the player intends to.
+Hitscan weapons raycast along the aim direction, projectile weapons spawn a bullet.
+An empty magazine starts a reload instead.
+*/
+pub fn fire_weapon_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    item_assets: Res<ItemAssets>,
+    mut rng: ResMut<GameRng>,
+    rapier_context: ReadDefaultRapierContext,
+    mut player_query: Query<
+        (Entity, &PlayerIntent, &EntityName, &Transform, &Direction, &mut Inventory, Option<&Reloading>),
+        (With<Player>, Without<WeaponSwitch>, Without<Stunned>, Without<Respawning>),
+    >,
+    target_query: Query<Entity, With<Player>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    // Height of the eyes above the player's center, matching the camera
+    const EYE_HEIGHT: f32 = 1.5;
+
+    for (entity, intent, name, transform, direction, mut inventory, reloading) in player_query.iter_mut() {
+        if !intent.fire || reloading.is_some() {
+            continue;
+        }
+        let Some(weapon) = inventory.weapons.active_weapon_mut() else {
+            continue;
+        };
+        let damage = weapon.damage;
+        let (damage_type, knockback, hit_stun) = (weapon.damage_type, weapon.knockback, weapon.hit_stun);
+        let source = DamageSource::new(Instigator::new(entity, name), &weapon.name);
+        let Some(ranged) = weapon.ranged.as_mut() else {
+            continue;
+        };
+
+        if ranged.magazine == 0 {
+            if ranged.can_reload() {
+                start_reload(&mut commands, entity, ranged);
+            }
+            continue;
+        }
+        if !ranged.can_fire() {
+            continue;
+        }
+        ranged.consume_shot();
+        commands.send_event(AttackEvent { attacker: entity });
+
+        let aim = rng.direction_in_cone(direction.direction, ranged.spread);
+        match ranged.fire_mode {
+            FireMode::Hitscan { range } => {
+                let origin = transform.translation + Vec3::Y * EYE_HEIGHT;
+                let filter = QueryFilter::default()
+                    .exclude_rigid_body(entity)
+                    .exclude_sensors();
+                if let Some((hit_entity, distance)) =
+                    rapier_context.single().cast_ray(origin, aim, range, true, filter)
+                {
+                    if target_query.contains(hit_entity) {
+                        debug!("Shot hit player {} at {:.1}m", hit_entity, distance);
+                        damage_events.send(DamageEvent {
+                            target: hit_entity,
+                            amount: damage,
+                            damage_type,
+                            knockback: aim * knockback,
+                            hit_stun,
+                            origin,
+                            point: origin + aim * distance,
+                            source,
+                        });
+                    }
+                }
+            }
+            FireMode::Projectile { speed, gravity_scale } => {
+                let bullet = spawn_thrown_object(
+                    &mut commands,
+                    ThrowParams::new(item_assets.bullet_mesh.clone(), item_assets.bullet_material.clone(), speed)
+                        .with_radius(0.1)
+                        .with_gravity_scale(gravity_scale)
+                        .with_damage(damage)
+                        .with_damage_type(damage_type)
+                        .with_knockback(knockback)
+                        .with_hit_stun(hit_stun),
+                    source,
+                    transform.translation,
+                    aim,
+                    time.elapsed_secs(),
+                );
+                commands.entity(bullet).insert(Bullet);
+            }
+        }
+    }
+}
+
+/// Starts reloading the held ranged weapon when the player intends to
+pub fn reload_input_system(
+    mut commands: Commands,
+    mut player_query: Query<
+        (Entity, &PlayerIntent, &mut Inventory),
+        (With<Player>, Without<Reloading>, Without<WeaponSwitch>),
+    >,
+) {
+    for (entity, intent, mut inventory) in player_query.iter_mut() {
+        if !intent.reload {
+            continue;
+        }
+        if let Some(ranged) = inventory
+            .weapons
+            .active_weapon_mut()
+            .and_then(|weapon| weapon.ranged.as_mut())
+            .filter(|ranged| ranged.can_reload())
+        {
+            start_reload(&mut commands, entity, ranged);
+        }
+    }
+}
+
+/// Finishes reloads once their timer runs out
+pub fn reload_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_query: Query<(Entity, &mut Inventory, &mut Reloading), With<Player>>,
+) {
+    for (entity, mut inventory, mut reloading) in player_query.iter_mut() {
+        if !reloading.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        commands.entity(entity).remove::<Reloading>();
+        if let Some(ranged) = inventory
+            .weapons
+            .active_weapon_mut()
+            .and_then(|weapon| weapon.ranged.as_mut())
+        {
+            ranged.reload();
+        }
+    }
+}
+
+// HELPER FUNCTION
+
+fn start_reload(commands: &mut Commands, entity: Entity, ranged: &RangedWeapon) {
+    commands.entity(entity).insert(Reloading {
+        timer: Timer::from_seconds(ranged.reload_time, TimerMode::Once),
+    });
+}
@@ -0,0 +1,166 @@
+use crate::components::combat::{DamageSource, DamageType, Respawning};
+use crate::components::player::{Player, PlayerStats};
+use crate::components::world::{OutsideZone, SafeZoneBoundary};
+use crate::events::combat::DamageEvent;
+use crate::events::notification::{NotificationEvent, NotificationPriority};
+use crate::resources::game::{GameMode, GameState, MatchSettings, WorldAttribute};
+use crate::resources::rng::GameRng;
+use crate::resources::zone::{SafeZone, ZONE_ESCALATION, ZONE_PHASES};
+use bevy::prelude::*;
+
+/// Starts the safe zone when a last player standing match begins, and turns it off otherwise
+pub fn reset_safe_zone_system(
+    state: Res<State<GameState>>,
+    settings: Res<MatchSettings>,
+    world_attribute: Res<WorldAttribute>,
+    mut zone: ResMut<SafeZone>,
+) {
+    if *state.get() == GameState::InProgress && settings.mode == GameMode::LastStanding {
+        zone.start(world_attribute.width, world_attribute.height);
+    } else {
+        zone.active = false;
+    }
+}
+
+/**
+Moves the safe zone through its phases.
+Each phase waits, then picks a smaller circle inside the current one and shrinks towards it.
+*/
+pub fn safe_zone_system(
+    time: Res<Time>,
+    mut zone: ResMut<SafeZone>,
+    mut rng: ResMut<GameRng>,
+    player_query: Query<Entity, With<Player>>,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    if !zone.active || zone.is_closed() {
+        return;
+    }
+    zone.timer.tick(time.delta());
+
+    if zone.shrinking {
+        let progress = zone.timer.fraction();
+        let ((from_center, from_radius), (to_center, to_radius)) = (zone.from, zone.to);
+        zone.center = from_center.lerp(to_center, progress);
+        zone.radius = from_radius.lerp(to_radius, progress);
+        if zone.timer.finished() {
+            zone.phase += 1;
+            zone.shrinking = false;
+            if let Some(next) = ZONE_PHASES.get(zone.phase) {
+                zone.timer = Timer::from_seconds(next.wait, TimerMode::Once);
+            }
+        }
+        return;
+    }
+
+    if zone.timer.finished() {
+        let phase = ZONE_PHASES[zone.phase];
+        let radius = zone.start_radius * phase.radius;
+        let center = next_zone_center(&zone, &mut rng, radius);
+        zone.from = (zone.center, zone.radius);
+        zone.to = (center, radius);
+        zone.shrinking = true;
+        zone.timer = Timer::from_seconds(phase.shrink, TimerMode::Once);
+        for player in player_query.iter() {
+            notification_events.send(NotificationEvent::new(
+                player,
+                "The zone is shrinking!",
+                NotificationPriority::High,
+            ));
+        }
+    }
+}
+
+/**
+Hurts living players outside the safe zone every tick.
+The damage grows with the phase and with every tick a player stays outside, and can't be blocked.
+*/
+pub fn zone_damage_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut zone: ResMut<SafeZone>,
+    mut player_query: Query<
+        (Entity, &Transform, &PlayerStats, Option<&mut OutsideZone>),
+        (With<Player>, Without<Respawning>),
+    >,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut notification_events: EventWriter<NotificationEvent>,
+) {
+    if !zone.active || !zone.damage_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for (player, transform, player_stats, outside) in player_query.iter_mut() {
+        if player_stats.health <= 0.0 {
+            continue;
+        }
+        if zone.contains(transform.translation) {
+            if outside.is_some() {
+                commands.entity(player).remove::<OutsideZone>();
+            }
+            continue;
+        }
+
+        let ticks = match outside {
+            Some(mut outside) => {
+                outside.ticks += 1;
+                outside.ticks
+            }
+            None => {
+                commands.entity(player).insert(OutsideZone { ticks: 1 });
+                notification_events.send(NotificationEvent::new(
+                    player,
+                    "You are outside the zone!",
+                    NotificationPriority::High,
+                ));
+                1
+            }
+        };
+        damage_events.send(DamageEvent {
+            target: player,
+            amount: zone.damage() * (1.0 + ZONE_ESCALATION * (ticks - 1) as f32),
+            damage_type: DamageType::Piercing,
+            knockback: Vec3::ZERO,
+            hit_stun: 0.0,
+            // Coming from the player's own position, so it can't be blocked
+            origin: transform.translation,
+            point: transform.translation,
+            source: DamageSource {
+                instigator: None,
+                cause: String::from("Zone"),
+            },
+        });
+    }
+}
+
+/// Keeps the wall at the edge of the safe zone, hiding it when the zone isn't used
+pub fn zone_boundary_system(
+    zone: Res<SafeZone>,
+    world_attribute: Res<WorldAttribute>,
+    mut boundary_query: Query<(&mut Transform, &mut Visibility), With<SafeZoneBoundary>>,
+) {
+    for (mut transform, mut visibility) in boundary_query.iter_mut() {
+        let shown = zone.active && zone.radius > 0.0;
+        visibility.set_if_neq(if shown { Visibility::Inherited } else { Visibility::Hidden });
+        if !shown {
+            continue;
+        }
+        transform.translation = Vec3::new(zone.center.x, world_attribute.wall_height / 2.0, zone.center.y);
+        transform.scale = Vec3::new(zone.radius, world_attribute.wall_height, zone.radius);
+    }
+}
+
+// HELPER FUNCTION
+
+/**
+Helper function to pick the center of the next zone.
+The next zone lies inside the current one and its center inside the arena.
+*/
+fn next_zone_center(zone: &SafeZone, rng: &mut GameRng, radius: f32) -> Vec2 {
+    let slack = (zone.radius - radius).max(0.0);
+    let angle = rng.range(0.0, std::f32::consts::TAU);
+    // sqrt keeps the centers evenly spread over the circle instead of bunching in the middle
+    let distance = slack * rng.next_f32().sqrt();
+    let center = zone.center + Vec2::from_angle(angle) * distance;
+    center.clamp(-zone.bounds, zone.bounds)
+}